@@ -0,0 +1,145 @@
+//! 区間加算・区間和での LazySegmentTree と MapLazySegmentTree の比較
+//! cargo bench --bench lazy_segment_tree
+
+use bibliotheca::data_structures::segment_trees::{
+    lazy_segment_tree::LazySegmentTree, map_lazy_segment_tree::MapLazySegmentTree,
+};
+use bibliotheca::utils::algebraic_traits::{Associative, MapMonoid, Pow, Zero};
+use bibliotheca::utils::monoids::Sum;
+use bibliotheca::utils::random::XorShift;
+use rand::{Rng, SeedableRng};
+use std::ops::{Add, Mul};
+use std::time::{Duration, Instant};
+
+const N: usize = 1 << 18;
+const Q: usize = 1 << 20;
+
+#[derive(Clone, Copy, PartialEq)]
+struct S(i64);
+
+impl Add for S {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        S(self.0 + rhs.0)
+    }
+}
+
+impl Associative for S {}
+
+impl Zero for S {
+    fn zero() -> Self {
+        S(0)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct E(i64);
+
+impl Add for E {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        E(self.0 + rhs.0)
+    }
+}
+
+impl Associative for E {}
+
+impl Zero for E {
+    fn zero() -> Self {
+        E(0)
+    }
+}
+
+impl Pow for E {
+    fn pow(&self, p: u32) -> Self {
+        E(self.0 * p as i64)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Mul<E> for S {
+    type Output = Self;
+    fn mul(self, e: E) -> Self {
+        S(self.0 + e.0)
+    }
+}
+
+struct AddSum;
+
+impl MapMonoid for AddSum {
+    type M = Sum<i64>;
+    type F = i64;
+    fn identity_map() -> i64 {
+        0
+    }
+    fn mapping(&f: &i64, x: &Sum<i64>, len: usize) -> Sum<i64> {
+        Sum(x.0 + f * len as i64)
+    }
+    fn composition(&f: &i64, &g: &i64) -> i64 {
+        f + g
+    }
+}
+
+/// (更新なら Some(x), 区間)
+fn queries() -> Vec<(Option<i64>, usize, usize)> {
+    let mut rng = XorShift::seed_from_u64(1);
+    (0..Q)
+        .map(|_| {
+            let l = rng.gen_range(0..N);
+            let r = rng.gen_range(l + 1..=N);
+            let x = if rng.gen_bool(0.5) {
+                Some(rng.gen_range(-100..100))
+            } else {
+                None
+            };
+            (x, l, r)
+        })
+        .collect()
+}
+
+/// 3 回走らせて最短の時間を取る
+fn measure<F: FnMut() -> i64>(name: &str, mut run: F) -> (Duration, i64) {
+    let mut elapsed = Duration::from_secs(std::u64::MAX);
+    let mut checksum = 0;
+    for _ in 0..3 {
+        let start = Instant::now();
+        checksum = run();
+        elapsed = elapsed.min(start.elapsed());
+    }
+    println!(
+        "{:<20} {:>8.3} s  (checksum {})",
+        name,
+        elapsed.as_secs_f64(),
+        checksum
+    );
+    (elapsed, checksum)
+}
+
+fn main() {
+    let queries = queries();
+    let old = measure("LazySegmentTree", || {
+        let mut seg = LazySegmentTree::<S, E>::from(&vec![S(0); N]);
+        let mut checksum = 0i64;
+        for &(x, l, r) in &queries {
+            match x {
+                Some(x) => seg.update(l..r, E(x)),
+                None => checksum = checksum.wrapping_add(seg.fold(l..r).0),
+            }
+        }
+        checksum
+    });
+    let new = measure("MapLazySegmentTree", || {
+        let mut seg = MapLazySegmentTree::<AddSum>::new(N);
+        let mut checksum = 0i64;
+        for &(x, l, r) in &queries {
+            match x {
+                Some(x) => seg.update(l..r, x),
+                None => checksum = checksum.wrapping_add(seg.fold(l..r).0),
+            }
+        }
+        checksum
+    });
+    assert_eq!(old.1, new.1);
+    let (old, new) = (old.0, new.0);
+    println!("speedup: {:.2}x", old.as_secs_f64() / new.as_secs_f64());
+}
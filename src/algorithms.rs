@@ -4,6 +4,7 @@ pub mod cartesian_tree;
 pub mod compress;
 pub mod convex_hull;
 pub mod dp;
+pub mod fftable;
 pub mod flow;
 pub mod graph;
 pub mod kitamasa;
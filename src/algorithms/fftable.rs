@@ -1,3 +1,5 @@
+use crate::utils::algebraic_traits::Ring;
+
 /// 高次の 2 冪根を持つ自然数で除算可能な [`Ring`](../type_traits/index.html) です。
 pub trait Fftable: Ring + Copy {
     /// 高次の 2 冪根です。
@@ -19,9 +21,9 @@ pub trait Fftable: Ring + Copy {
     /// # Examples
     ///
     /// ```
-    /// use fft::Fftable;
-    /// type Fp = fp::F998244353;
-    /// let a = Fp::root_seq::<fft::Forward>();
+    /// use bibliotheca::algorithms::fftable::{Fftable, Forward};
+    /// use bibliotheca::utils::fp::F998244353 as Fp;
+    /// let a = Fp::root_seq::<Forward>();
     /// assert_eq!(a[0], Fp::new(1));
     /// assert_eq!(a[1], Fp::new(-1));
     /// ```
@@ -40,7 +42,7 @@ pub trait Fftable: Ring + Copy {
         let mut res = Vec::with_capacity(Self::lg_ord());
         for _ in 0..Self::lg_ord() {
             res.push(root);
-            root *= root;
+            root = root * root;
         }
         res.push(root);
         res.reverse();
@@ -48,8 +50,29 @@ pub trait Fftable: Ring + Copy {
     }
 }
 
+/// [`root`](trait.Fftable.html#tymethod.root) と [`root_inv`](trait.Fftable.html#tymethod.root_inv)
+/// の呼び分けに使います。
+pub trait DirectionTag {
+    /// [`root`](trait.Fftable.html#tymethod.root) か [`root_inv`](trait.Fftable.html#tymethod.root_inv)
+    /// を呼びます。
+    fn root<T: Fftable>() -> T;
+}
+/// [`root`](trait.Fftable.html#tymethod.root) を担当します。
+pub struct Forward {}
+impl DirectionTag for Forward {
+    fn root<T: Fftable>() -> T {
+        T::root()
+    }
+}
+/// [`root_inv`](trait.Fftable.html#tymethod.root_inv) を担当します。
+pub struct Backward {}
+impl DirectionTag for Backward {
+    fn root<T: Fftable>() -> T {
+        T::root_inv()
+    }
+}
 
-use fp::F998244353;
+use crate::utils::fp::F998244353;
 
 impl Fftable for F998244353 {
     fn root() -> F998244353 {
@@ -64,4 +87,28 @@ impl Fftable for F998244353 {
     fn div_assign_by_usize(&mut self, den: usize) {
         *self /= F998244353::new(den as i64)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_seq() {
+        type Fp = F998244353;
+        let forward = Fp::root_seq::<Forward>();
+        let backward = Fp::root_seq::<Backward>();
+        assert_eq!(forward.len(), Fp::lg_ord() + 1);
+        assert_eq!(forward[0], Fp::new(1));
+        assert_eq!(forward[1], Fp::new(-1));
+        for (&x, &y) in forward.iter().zip(&backward) {
+            assert_eq!(x * y, Fp::new(1));
+        }
+        for w in forward.windows(2) {
+            assert_eq!(w[1] * w[1], w[0]);
+        }
+        let mut x = Fp::new(10);
+        x.div_assign_by_usize(4);
+        assert_eq!(x * Fp::new(4), Fp::new(10));
+    }
+}
@@ -1,4 +1,5 @@
 // TODO: verify
+// 剰余を取るなら utils::linear_recurrence::nth_term の方が速い (O(k logk logn))
 // ------------ Kitamasa's algorithm start ------------
 
 /// d_i = d[i] (0 <= i < k),
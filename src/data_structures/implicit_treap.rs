@@ -7,6 +7,7 @@
 use std::ops::{Mul, Range};
 use crate::utils::algebraic_traits::{Monoid, Pow};
 use crate::utils::random::XorShift;
+use rand::RngCore;
 
 struct Node<T, E> {
     value: T,
@@ -287,7 +288,7 @@ impl<T: Monoid + Mul<E, Output=T>, E: Monoid + Pow> Treap<T, E> {
 
     pub fn insert(&mut self, pos: usize, val: T) {
         let root = self.root.take();
-        let new_node = Node::new(val, self.rand.gen() as usize);
+        let new_node = Node::new(val, self.rand.next_u64() as usize);
         self.root = if let Some(node) = root {
             Some(node.insert(pos, new_node))
         } else {
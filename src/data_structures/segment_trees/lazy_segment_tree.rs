@@ -6,7 +6,7 @@ use crate::utils::{
 use std::ops::{Mul, RangeBounds};
 
 // * verified: https://judge.yosupo.jp/submission/28350
-// TODO: it might be able to make it faster
+// NOTE: 作用が区間の長さだけを使うなら map_lazy_segment_tree::MapLazySegmentTree の方が速い
 // ------------ Lazy Segment Tree start ------------
 
 #[derive(Clone)]
@@ -41,7 +41,7 @@ impl<T: Monoid + Mul<E, Output = T>, E: Monoid + Pow> LazySegmentTree<T, E> {
 
     #[inline]
     fn degree(&self, i: usize) -> u32 {
-        1 << i.leading_zeros() as usize - (64 - self.dep - 1)
+        1 << (i.leading_zeros() as usize - (64 - self.dep))
     }
 
     fn effect(&mut self, i: usize, e: &E) {
@@ -228,3 +228,78 @@ mod rmq_ruq_test {
         assert_eq!(seg.fold(1..3).0, 2);
     }
 }
+
+#[cfg(test)]
+mod rsq_raq_test {
+    use super::*;
+    use crate::utils::algebraic_traits::*;
+    use crate::utils::monoids::Sum;
+    use std::ops::{Add, Mul};
+
+    #[derive(Clone, PartialEq)]
+    struct Ra(i64);
+
+    impl Add for Ra {
+        type Output = Self;
+        fn add(self, right: Self) -> Self {
+            Ra(self.0 + right.0)
+        }
+    }
+
+    impl Associative for Ra {}
+
+    impl Zero for Ra {
+        fn zero() -> Self {
+            Ra(0)
+        }
+    }
+
+    impl Pow for Ra {
+        fn pow(&self, p: u32) -> Self {
+            Ra(self.0 * p as i64)
+        }
+    }
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    impl Mul<Ra> for Sum<i64> {
+        type Output = Self;
+        fn mul(self, e: Ra) -> Self {
+            Sum(self.0 + e.0)
+        }
+    }
+
+    #[test]
+    fn rsq_raq_test() {
+        let mut seg = LazySegmentTree::from(&vec![Sum(0i64); 5]);
+        seg.update(0..3, Ra(2));
+        seg.update(2..5, Ra(1));
+        assert_eq!(seg.fold(0..5).0, 9);
+        assert_eq!(seg.fold(2..3).0, 3);
+        assert_eq!(seg.fold(1..4).0, 6);
+    }
+
+    // 葉の次数は 1 (以前は 2 として扱われ、葉に直接作用すると値が 2 倍になっていた)
+    #[test]
+    fn leaf_degree_test() {
+        use crate::utils::random::XorShift;
+        use rand::{Rng, SeedableRng};
+        let mut rng = XorShift::seed_from_u64(1);
+        for n in 1..20 {
+            let mut seg = LazySegmentTree::from(&vec![Sum(0i64); n]);
+            let mut brute = vec![0i64; n];
+            for _ in 0..100 {
+                let l = rng.gen_range(0..n);
+                let r = rng.gen_range(l + 1..=n);
+                let x = rng.gen_range(-10..10);
+                seg.update(l..r, Ra(x));
+                brute[l..r].iter_mut().for_each(|v| *v += x);
+                for (i, &v) in brute.iter().enumerate() {
+                    assert_eq!(seg.fold(i..i + 1).0, v);
+                }
+                let l = rng.gen_range(0..n);
+                let r = rng.gen_range(l..=n);
+                assert_eq!(seg.fold(l..r).0, brute[l..r].iter().sum::<i64>());
+            }
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use crate::utils::{
+    algebraic_traits::{MapMonoid, Zero},
+    bounds::bounds_within,
+};
+
+use std::ops::{Range, RangeBounds};
+
+// ------------ Map Lazy Segment Tree start ------------
+
+/// 作用に区間の長さを渡す遅延セグメント木
+/// LazySegmentTree と違い、作用の累乗 (Pow) を計算しない
+pub struct MapLazySegmentTree<F: MapMonoid> {
+    n: usize,
+    size: usize,
+    log: u32,
+    data: Vec<F::M>,
+    lazy: Vec<F::F>,
+}
+
+impl<F: MapMonoid> MapLazySegmentTree<F> {
+    pub fn new(n: usize) -> Self {
+        Self::from(vec![F::M::zero(); n])
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    #[inline]
+    fn pull(&mut self, k: usize) {
+        self.data[k] = self.data[k << 1].clone() + self.data[(k << 1) + 1].clone();
+    }
+
+    /// 長さ width の区間を表す節点 k に f を作用させる
+    #[inline]
+    fn all_apply(&mut self, k: usize, f: &F::F, width: usize) {
+        self.data[k] = F::mapping(f, &self.data[k], width);
+        if k < self.size {
+            self.lazy[k] = F::composition(f, &self.lazy[k]);
+        }
+    }
+
+    /// 高さ h (葉は 0) の節点 k の作用を子に降ろす
+    #[inline]
+    fn push(&mut self, k: usize, h: u32) {
+        let id = F::identity_map();
+        if self.lazy[k] != id {
+            let f = std::mem::replace(&mut self.lazy[k], id);
+            let width = 1 << (h - 1);
+            self.all_apply(k << 1, &f, width);
+            self.all_apply((k << 1) + 1, &f, width);
+        }
+    }
+
+    /// l と r の境界をまたぐ祖先の作用をすべて子に降ろす
+    fn push_bounds(&mut self, l: usize, r: usize) {
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i, i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i, i);
+            }
+        }
+    }
+
+    pub fn set(&mut self, p: usize, x: F::M) {
+        assert!(p < self.n, "index out of range: {}", p);
+        let p = p + self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i, i);
+        }
+        self.data[p] = x;
+        for i in 1..=self.log {
+            self.pull(p >> i);
+        }
+    }
+
+    pub fn get(&mut self, p: usize) -> F::M {
+        assert!(p < self.n, "index out of range: {}", p);
+        let p = p + self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i, i);
+        }
+        self.data[p].clone()
+    }
+
+    pub fn fold<R: RangeBounds<usize>>(&mut self, rng: R) -> F::M {
+        let Range { start, end } = bounds_within(rng, self.n);
+        if start == end {
+            return F::M::zero();
+        }
+        let mut l = start + self.size;
+        let mut r = end + self.size;
+        self.push_bounds(l, r);
+        let mut sml = F::M::zero();
+        let mut smr = F::M::zero();
+        while l < r {
+            if l & 1 == 1 {
+                sml = sml + self.data[l].clone();
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                smr = self.data[r].clone() + smr;
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        sml + smr
+    }
+
+    pub fn update<R: RangeBounds<usize>>(&mut self, rng: R, f: F::F) {
+        let Range { start, end } = bounds_within(rng, self.n);
+        if start == end {
+            return;
+        }
+        let l = start + self.size;
+        let r = end + self.size;
+        self.push_bounds(l, r);
+        {
+            let (mut l, mut r) = (l, r);
+            let mut width = 1;
+            while l < r {
+                if l & 1 == 1 {
+                    self.all_apply(l, &f, width);
+                    l += 1;
+                }
+                if r & 1 == 1 {
+                    r -= 1;
+                    self.all_apply(r, &f, width);
+                }
+                l >>= 1;
+                r >>= 1;
+                width <<= 1;
+            }
+        }
+        for i in 1..=self.log {
+            if ((l >> i) << i) != l {
+                self.pull(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.pull((r - 1) >> i);
+            }
+        }
+    }
+}
+
+impl<F: MapMonoid> From<Vec<F::M>> for MapLazySegmentTree<F> {
+    fn from(v: Vec<F::M>) -> Self {
+        let n = v.len();
+        let size = n.next_power_of_two();
+        let log = size.trailing_zeros();
+        let mut data = vec![F::M::zero(); size << 1];
+        for (d, x) in data[size..].iter_mut().zip(v) {
+            *d = x;
+        }
+        let mut tree = Self {
+            n,
+            size,
+            log,
+            data,
+            lazy: vec![F::identity_map(); size],
+        };
+        for k in (1..size).rev() {
+            tree.pull(k);
+        }
+        tree
+    }
+}
+
+// ------------ Map Lazy Segment Tree end ------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::monoids::{Affine, Last, Min, Sum};
+    use crate::utils::random::XorShift;
+    use rand::{Rng, SeedableRng};
+    use std::ops::Mul;
+
+    // 区間アフィン変換・区間和
+    struct AffineSum;
+
+    impl MapMonoid for AffineSum {
+        type M = Sum<i64>;
+        type F = Affine<i64>;
+        fn identity_map() -> Affine<i64> {
+            Affine(1, 0)
+        }
+        fn mapping(f: &Affine<i64>, x: &Sum<i64>, len: usize) -> Sum<i64> {
+            Sum(f.0 * x.0 + f.1 * len as i64)
+        }
+        fn composition(f: &Affine<i64>, g: &Affine<i64>) -> Affine<i64> {
+            *g + *f
+        }
+    }
+
+    #[test]
+    fn test_affine_sum() {
+        let mut rng = XorShift::seed_from_u64(1);
+        for n in 1..20 {
+            let mut a = (0..n).map(|_| rng.gen_range(-5..5)).collect::<Vec<i64>>();
+            let mut seg = MapLazySegmentTree::<AffineSum>::from(
+                a.iter().map(|&x| Sum(x)).collect::<Vec<_>>(),
+            );
+            for _ in 0..200 {
+                let l = rng.gen_range(0..=n);
+                let r = rng.gen_range(l..=n);
+                match rng.gen_range(0..3) {
+                    0 => {
+                        let f = Affine(rng.gen_range(-2..=2), rng.gen_range(-5..5));
+                        a[l..r].iter_mut().for_each(|x| *x = f.apply(*x));
+                        seg.update(l..r, f);
+                    }
+                    1 if l < n => {
+                        let x = rng.gen_range(-5..5);
+                        a[l] = x;
+                        seg.set(l, Sum(x));
+                        assert_eq!(seg.get(l), Sum(x));
+                    }
+                    _ => assert_eq!(seg.fold(l..r), Sum(a[l..r].iter().sum())),
+                }
+            }
+            assert_eq!(seg.fold(..), Sum(a.iter().sum()));
+        }
+    }
+
+    impl Mul<Last<usize>> for Min<usize> {
+        type Output = Self;
+        fn mul(self, f: Last<usize>) -> Self {
+            match f.0 {
+                Some(x) => Min(x),
+                None => self,
+            }
+        }
+    }
+
+    #[test]
+    fn test_pow_action() {
+        // LazySegmentTree と同じ (T, E) もそのまま使える
+        let mut seg = MapLazySegmentTree::<(Min<usize>, Last<usize>)>::from(vec![Min(5); 3]);
+        seg.update(0..2, Last(Some(1)));
+        seg.update(1..3, Last(Some(3)));
+        seg.update(2..3, Last(Some(2)));
+        assert_eq!(seg.fold(0..3), Min(1));
+        assert_eq!(seg.fold(1..3), Min(2));
+        assert_eq!(seg.len(), 3);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod dynamic_segment_tree;
 pub mod lazy_segment_tree;
+pub mod map_lazy_segment_tree;
 pub mod segment_tree;
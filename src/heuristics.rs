@@ -1,8 +1,22 @@
+pub mod acceptance;
 pub mod annealing;
 pub mod beam_search;
+pub mod budget;
+pub mod continuous;
 pub mod genetic;
 pub mod hill_climb;
+pub mod iterated_local_search;
+pub mod mcts;
+pub mod parallel;
+pub mod permutation;
 pub mod random_search;
+pub mod schedule;
+pub mod tabu_search;
+pub mod telemetry;
+#[cfg(test)]
+mod test_problems;
+
+use telemetry::Record;
 
 pub trait State: Clone + PartialEq {}
 impl<S: Clone + PartialEq> State for S {}
@@ -13,8 +27,21 @@ pub struct StateWrapper<S: State> {
     score: Option<f64>,
 }
 
-#[allow(dead_code)]
 impl<S: State> StateWrapper<S> {
+    pub fn new(state: S) -> Self {
+        Self { state, score: None }
+    }
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+    /// 状態を書き換えるとキャッシュされたスコアは破棄される
+    pub fn state_mut(&mut self) -> &mut S {
+        self.score = None;
+        &mut self.state
+    }
+    pub fn into_state(self) -> S {
+        self.state
+    }
     fn get_score(&self) -> Option<f64> {
         self.score
     }
@@ -23,8 +50,43 @@ impl<S: State> StateWrapper<S> {
     }
 }
 
+/// generate や neighbor で使う乱数は実装側で持つ
+/// utils::random::XorShift などのシード付きの乱数を使えば探索を再現できる
 pub trait Metaheuristics<S: State> {
     fn evaluate(&mut self, st: &mut StateWrapper<S>) -> f64;
     fn generate(&mut self) -> StateWrapper<S>;
     fn neighbor(&mut self, st: &StateWrapper<S>) -> StateWrapper<S>;
+
+    /// evaluate の結果を st にキャッシュし、キャッシュがあれば evaluate を呼ばない
+    fn score(&mut self, st: &mut StateWrapper<S>) -> f64 {
+        if let Some(score) = st.get_score() {
+            return score;
+        }
+        let score = self.evaluate(st);
+        st.set_score(score);
+        score
+    }
+
+    /// 探索の各反復の終わりに呼ばれる
+    fn observe(&mut self, _record: &Record) {}
+}
+
+/// 状態を複製せずに書き換えながら探索するための遷移
+pub trait MoveMetaheuristics<S: State>: Metaheuristics<S> {
+    type Move;
+    fn propose(&mut self, st: &StateWrapper<S>) -> Self::Move;
+    fn apply(&mut self, st: &mut StateWrapper<S>, mv: &Self::Move);
+    /// apply(st, mv) を取り消す
+    fn rollback(&mut self, st: &mut StateWrapper<S>, mv: &Self::Move);
+
+    /// mv を適用したときのスコアの変化量。st は呼び出し前の状態に戻っていること
+    /// 既定の実装は apply, evaluate, rollback を行うので、差分で計算できるなら上書きする
+    fn delta(&mut self, st: &mut StateWrapper<S>, mv: &Self::Move) -> f64 {
+        let before = self.score(st);
+        self.apply(st, mv);
+        let after = self.evaluate(st);
+        self.rollback(st, mv);
+        st.set_score(before);
+        after - before
+    }
 }
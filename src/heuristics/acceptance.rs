@@ -0,0 +1,153 @@
+use rand::Rng;
+
+/// スコアが current の状態から next の状態へ遷移するかどうかを決める
+pub trait Acceptance {
+    fn accept<R: Rng>(&mut self, rng: &mut R, current: f64, next: f64, temperature: f64) -> bool;
+}
+
+/// 確率 exp((next - current) / temperature) で悪化を受理する
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Metropolis;
+
+impl Acceptance for Metropolis {
+    fn accept<R: Rng>(&mut self, rng: &mut R, current: f64, next: f64, temperature: f64) -> bool {
+        next >= current || rng.gen::<f64>() < ((next - current) / temperature).exp()
+    }
+}
+
+/// 悪化が temperature 以下なら受理する
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Threshold;
+
+impl Acceptance for Threshold {
+    fn accept<R: Rng>(&mut self, _rng: &mut R, current: f64, next: f64, temperature: f64) -> bool {
+        next >= current - temperature
+    }
+}
+
+/// 改善なら受理し、悪化は大きさによらず確率 temperature で受理する
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FixedProbability;
+
+impl Acceptance for FixedProbability {
+    fn accept<R: Rng>(&mut self, rng: &mut R, current: f64, next: f64, temperature: f64) -> bool {
+        next > current || rng.gen::<f64>() < temperature
+    }
+}
+
+/// 水位 level 以上なら受理し、受理するたびに水位を rain だけ上げる
+#[derive(Clone, Debug)]
+pub struct GreatDeluge {
+    pub level: f64,
+    pub rain: f64,
+}
+
+impl Acceptance for GreatDeluge {
+    fn accept<R: Rng>(
+        &mut self,
+        _rng: &mut R,
+        _current: f64,
+        next: f64,
+        _temperature: f64,
+    ) -> bool {
+        if next >= self.level {
+            self.level += self.rain;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// length 回前の判定時点のスコア以上か、改善していれば受理する
+#[derive(Clone, Debug)]
+pub struct LateAcceptance {
+    history: Vec<f64>,
+    length: usize,
+    index: usize,
+}
+
+impl LateAcceptance {
+    pub fn new(length: usize) -> Self {
+        assert!(length > 0, "length must be positive");
+        Self {
+            history: Vec::with_capacity(length),
+            length,
+            index: 0,
+        }
+    }
+}
+
+impl Acceptance for LateAcceptance {
+    fn accept<R: Rng>(&mut self, _rng: &mut R, current: f64, next: f64, _temperature: f64) -> bool {
+        if self.history.is_empty() {
+            self.history = vec![current; self.length];
+        }
+        let accepted = next >= current || next >= self.history[self.index];
+        self.history[self.index] = if accepted { next } else { current };
+        self.index = (self.index + 1) % self.length;
+        accepted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::random::XorShift;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_metropolis() {
+        let mut rng = XorShift::seed_from_u64(1);
+        assert!(Metropolis.accept(&mut rng, 1., 2., 1e-9));
+        assert!(!Metropolis.accept(&mut rng, 2., 1., 1e-9));
+        let accepted = (0..10000)
+            .filter(|_| Metropolis.accept(&mut rng, 2., 1., 1.))
+            .count();
+        assert!((3000..4300).contains(&accepted));
+    }
+
+    #[test]
+    fn test_threshold() {
+        let mut rng = XorShift::seed_from_u64(2);
+        assert!(Threshold.accept(&mut rng, 2., 1.5, 0.5));
+        assert!(!Threshold.accept(&mut rng, 2., 1.4, 0.5));
+    }
+
+    #[test]
+    fn test_fixed_probability() {
+        let mut rng = XorShift::seed_from_u64(3);
+        assert!(FixedProbability.accept(&mut rng, 1., 2., 0.));
+        assert!(!FixedProbability.accept(&mut rng, 2., 1., 0.));
+        assert!(FixedProbability.accept(&mut rng, 2., -100., 1.));
+        let accepted = (0..10000)
+            .filter(|_| FixedProbability.accept(&mut rng, 2., 1., 0.3))
+            .count();
+        assert!((2500..3500).contains(&accepted));
+    }
+
+    #[test]
+    fn test_great_deluge() {
+        let mut rng = XorShift::seed_from_u64(4);
+        let mut acc = GreatDeluge {
+            level: 0.,
+            rain: 1.,
+        };
+        assert!(acc.accept(&mut rng, 5., 0.5, 0.));
+        assert!(!acc.accept(&mut rng, 5., 0.5, 0.));
+        assert!(acc.accept(&mut rng, 0.5, 1., 0.));
+        assert!(!acc.accept(&mut rng, 0.5, 1., 0.));
+    }
+
+    #[test]
+    fn test_late_acceptance() {
+        let mut rng = XorShift::seed_from_u64(5);
+        let mut acc = LateAcceptance::new(2);
+        assert!(acc.accept(&mut rng, 5., 5., 0.));
+        assert!(!acc.accept(&mut rng, 5., 4., 0.));
+        assert!(!acc.accept(&mut rng, 5., 4., 0.));
+        assert!(acc.accept(&mut rng, 5., 6., 0.));
+        assert!(acc.accept(&mut rng, 6., 5.5, 0.));
+        assert!(!acc.accept(&mut rng, 5.5, 5.2, 0.));
+    }
+}
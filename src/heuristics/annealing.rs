@@ -1,71 +1,297 @@
-use crate::heuristics::{Metaheuristics, State};
-use rand::{thread_rng, Rng};
-use std::time::{Duration, Instant};
+use crate::heuristics::{
+    acceptance::{Acceptance, FixedProbability, Metropolis},
+    budget::{Budget, Timer},
+    schedule::{ExponentialDecay, Geometric, Schedule},
+    telemetry::Record,
+    Metaheuristics, MoveMetaheuristics, State, StateWrapper,
+};
+use rand::Rng;
 
-pub fn annealing<S: State, T: Metaheuristics<S>>(
+/// schedule と acceptance を組み合わせた焼きなまし
+/// progress には予算の消費割合を渡す
+pub fn anneal<S, T, Sc, A, B, R>(
     problem: &mut T,
-    runtime: Duration,
-    mut temprature: f64,
-    dt: f64,
-) -> S {
-    fn probability(current_score: f64, next_score: f64, temprature: f64) -> f64 {
-        if current_score > next_score {
-            1.
-        } else {
-            ((current_score - next_score) / temprature).exp()
-        }
-    }
-    let mut rng = thread_rng();
-    let mut current_candidate = problem.generate();
-    let mut best_candidate = current_candidate.clone();
-    let start_time = Instant::now();
+    budget: B,
+    schedule: Sc,
+    acceptance: A,
+    rng: &mut R,
+) -> S
+where
+    S: State,
+    T: Metaheuristics<S>,
+    Sc: Schedule,
+    A: Acceptance,
+    B: Into<Budget>,
+    R: Rng,
+{
+    let initial = problem.generate();
+    anneal_from(problem, initial, budget, schedule, acceptance, rng).state
+}
 
-    while Instant::now().duration_since(start_time) < runtime {
-        let mut next_candidate = problem.neighbor(&best_candidate);
+/// initial から始めて、最良の状態を返す
+pub fn anneal_from<S, T, Sc, A, B, R>(
+    problem: &mut T,
+    initial: StateWrapper<S>,
+    budget: B,
+    mut schedule: Sc,
+    mut acceptance: A,
+    rng: &mut R,
+) -> StateWrapper<S>
+where
+    S: State,
+    T: Metaheuristics<S>,
+    Sc: Schedule,
+    A: Acceptance,
+    B: Into<Budget>,
+    R: Rng,
+{
+    let mut current_candidate = initial;
+    let mut current_score = problem.score(&mut current_candidate);
+    let mut best_candidate = current_candidate.clone();
+    let mut best_score = current_score;
+    let mut accepted = 0;
+    let mut timer = Timer::new(budget);
 
-        if problem.evaluate(&mut next_candidate) > problem.evaluate(&mut best_candidate) {
-            best_candidate = next_candidate.clone();
-        }
+    while timer.tick() {
+        let temprature = schedule.temperature(timer.progress());
+        let mut next_candidate = problem.neighbor(&current_candidate);
+        let next_score = problem.score(&mut next_candidate);
 
-        if rng.gen::<f64>()
-            < probability(
-                problem.evaluate(&mut current_candidate),
-                problem.evaluate(&mut next_candidate),
-                temprature,
-            )
-        {
+        if acceptance.accept(rng, current_score, next_score, temprature) {
+            accepted += 1;
             current_candidate = next_candidate;
+            current_score = next_score;
+            if current_score > best_score {
+                best_score = current_score;
+                best_candidate = current_candidate.clone();
+            }
         }
-        temprature *= dt;
+        let record = Record::new(
+            &timer,
+            current_score,
+            best_score,
+            Some(temprature),
+            Some(accepted),
+        );
+        problem.observe(&record);
     }
-    best_candidate.state
+    best_candidate
 }
 
-pub fn annealing_easy<S: State, T: Metaheuristics<S>>(problem: &mut T, runtime: Duration) -> S {
-    let probability = |t: f64| (-10.0 * t.powf(3.0)).exp();
+pub fn annealing<S: State, T: Metaheuristics<S>, B: Into<Budget>, R: Rng>(
+    problem: &mut T,
+    budget: B,
+    temprature: f64,
+    dt: f64,
+    rng: &mut R,
+) -> S {
+    anneal(
+        problem,
+        budget,
+        Geometric::new(temprature, dt),
+        Metropolis,
+        rng,
+    )
+}
+
+/// 悪化を確率 exp(-10 t^3) で受理する焼きなまし (t は予算の消費割合)
+pub fn annealing_easy<S: State, T: Metaheuristics<S>, B: Into<Budget>, R: Rng>(
+    problem: &mut T,
+    budget: B,
+    rng: &mut R,
+) -> S {
+    anneal(
+        problem,
+        budget,
+        ExponentialDecay {
+            rate: 10.,
+            power: 3.,
+        },
+        FixedProbability,
+        rng,
+    )
+}
 
-    let mut rng = thread_rng();
+/// anneal と同じ遷移を、状態を複製せず MoveMetaheuristics の差分で行う
+/// 複製は最良解を更新したときのみ行う
+pub fn anneal_delta<S, T, Sc, A, B, R>(
+    problem: &mut T,
+    budget: B,
+    mut schedule: Sc,
+    mut acceptance: A,
+    rng: &mut R,
+) -> S
+where
+    S: State,
+    T: MoveMetaheuristics<S>,
+    Sc: Schedule,
+    A: Acceptance,
+    B: Into<Budget>,
+    R: Rng,
+{
     let mut current_candidate = problem.generate();
+    let mut current_score = problem.score(&mut current_candidate);
     let mut best_candidate = current_candidate.clone();
-    let runtime_as_milli = runtime.as_millis() as f64;
-    let start_time = Instant::now();
+    let mut best_score = current_score;
+    let mut accepted = 0;
+    let mut timer = Timer::new(budget);
 
-    loop {
-        let now_time = Instant::now().duration_since(start_time);
-        if now_time > runtime {
-            break;
-        }
-        let now_as_milli = now_time.as_millis() as f64;
-        let mut next_candidate = problem.neighbor(&best_candidate);
+    while timer.tick() {
+        let temprature = schedule.temperature(timer.progress());
+        let mv = problem.propose(&current_candidate);
+        let delta = problem.delta(&mut current_candidate, &mv);
 
-        if problem.evaluate(&mut next_candidate) > problem.evaluate(&mut current_candidate)
-            || rng.gen::<f64>() < probability(now_as_milli / runtime_as_milli)
-        {
-            current_candidate = next_candidate;
-            if problem.evaluate(&mut current_candidate) > problem.evaluate(&mut best_candidate) {
+        if acceptance.accept(rng, current_score, current_score + delta, temprature) {
+            accepted += 1;
+            problem.apply(&mut current_candidate, &mv);
+            current_score += delta;
+            current_candidate.set_score(current_score);
+            if current_score > best_score {
+                best_score = current_score;
                 best_candidate = current_candidate.clone();
             }
         }
+        let record = Record::new(
+            &timer,
+            current_score,
+            best_score,
+            Some(temprature),
+            Some(accepted),
+        );
+        problem.observe(&record);
     }
     best_candidate.state
 }
+
+pub fn annealing_delta<S: State, T: MoveMetaheuristics<S>, B: Into<Budget>, R: Rng>(
+    problem: &mut T,
+    budget: B,
+    temprature: f64,
+    dt: f64,
+    rng: &mut R,
+) -> S {
+    anneal_delta(
+        problem,
+        budget,
+        Geometric::new(temprature, dt),
+        Metropolis,
+        rng,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::random::XorShift;
+    use rand::SeedableRng;
+    use std::time::Duration;
+
+    // -(x - 37)^2 を最大化する
+    struct Parabola(XorShift);
+
+    impl Parabola {
+        fn new(seed: u64) -> Self {
+            Parabola(XorShift::seed_from_u64(seed))
+        }
+    }
+
+    impl Metaheuristics<i64> for Parabola {
+        fn evaluate(&mut self, st: &mut StateWrapper<i64>) -> f64 {
+            -((st.state() - 37) * (st.state() - 37)) as f64
+        }
+        fn generate(&mut self) -> StateWrapper<i64> {
+            StateWrapper::new(self.0.gen_range(-1000..1000))
+        }
+        fn neighbor(&mut self, st: &StateWrapper<i64>) -> StateWrapper<i64> {
+            StateWrapper::new(st.state() + self.propose(st))
+        }
+    }
+
+    impl MoveMetaheuristics<i64> for Parabola {
+        type Move = i64;
+        fn propose(&mut self, _st: &StateWrapper<i64>) -> i64 {
+            if self.0.gen_bool(0.5) {
+                1
+            } else {
+                -1
+            }
+        }
+        fn apply(&mut self, st: &mut StateWrapper<i64>, mv: &i64) {
+            *st.state_mut() += mv;
+        }
+        fn rollback(&mut self, st: &mut StateWrapper<i64>, mv: &i64) {
+            *st.state_mut() -= mv;
+        }
+    }
+
+    #[test]
+    fn test_annealing() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let x = annealing(&mut Parabola::new(1), 20000, 100., 0.999, &mut rng);
+        assert_eq!(x, 37);
+        let x = annealing_easy(&mut Parabola::new(1), Duration::from_millis(50), &mut rng);
+        assert_eq!(x, 37);
+    }
+
+    #[test]
+    fn test_annealing_delta() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let x = annealing_delta(&mut Parabola::new(1), 20000, 100., 0.999, &mut rng);
+        assert_eq!(x, 37);
+    }
+
+    #[test]
+    fn test_anneal_combinations() {
+        use crate::heuristics::acceptance::*;
+        use crate::heuristics::schedule::*;
+        let mut rng = XorShift::seed_from_u64(1);
+        let linear = || Linear {
+            start: 100.,
+            end: 0.1,
+        };
+        let proportional = || TimeProportional {
+            start: 100.,
+            end: 0.1,
+        };
+        let x = anneal(&mut Parabola::new(2), 20000, linear(), Metropolis, &mut rng);
+        assert_eq!(x, 37);
+        let x = anneal(
+            &mut Parabola::new(3),
+            20000,
+            proportional(),
+            Threshold,
+            &mut rng,
+        );
+        assert_eq!(x, 37);
+        let reheating = Reheating::new(linear(), 3);
+        let x = anneal(
+            &mut Parabola::new(4),
+            20000,
+            reheating,
+            Metropolis,
+            &mut rng,
+        );
+        assert_eq!(x, 37);
+        // 水位の上昇が勾配より速くなると止まるので、最適解の近くまでしか進まない
+        let deluge = GreatDeluge {
+            level: -1.1e6,
+            rain: 20.,
+        };
+        let x = anneal_delta(&mut Parabola::new(5), 200000, linear(), deluge, &mut rng);
+        assert!((x - 37).abs() <= 15);
+        let late = LateAcceptance::new(10);
+        let x = anneal_delta(&mut Parabola::new(6), 20000, proportional(), late, &mut rng);
+        assert_eq!(x, 37);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let run = |seed| {
+            let mut rng = XorShift::seed_from_u64(seed);
+            annealing(&mut Parabola::new(seed), 300, 100., 0.99, &mut rng)
+        };
+        assert_eq!(run(10), run(10));
+        assert_eq!(run(20), run(20));
+        assert_ne!(run(10), 37);
+    }
+}
@@ -1 +1,159 @@
+use crate::heuristics::{
+    budget::{Budget, Timer},
+    telemetry::Record,
+    Metaheuristics, State, StateWrapper,
+};
+use std::collections::HashSet;
 
+pub trait BeamSearch<S: State>: Metaheuristics<S> {
+    type Action: Clone;
+    /// turn 手目の状態 st から遷移できる (行動, 遷移先) を列挙する
+    fn expand(&mut self, st: &StateWrapper<S>, turn: usize)
+        -> Vec<(Self::Action, StateWrapper<S>)>;
+    /// 同一の状態は同じ値を返すこと
+    fn hash(&self, st: &StateWrapper<S>) -> u64;
+}
+
+struct Node<A> {
+    parent: usize,
+    action: Option<A>,
+}
+
+/// generate() から max_turn 手進めた状態のうち最良のものと、そこに至る行動列を返す
+/// 予算切れまたは遷移先がなくなった場合はその時点のビームから最良のものを返す
+/// 反復回数の予算は手数として数える
+pub fn beam_search<S: State, T: BeamSearch<S>, B: Into<Budget>>(
+    problem: &mut T,
+    budget: B,
+    width: usize,
+    max_turn: usize,
+) -> (S, Vec<T::Action>) {
+    assert!(width > 0, "beam width must be positive");
+    let mut timer = Timer::new(budget);
+    let mut nodes = vec![Node {
+        parent: 0,
+        action: None,
+    }];
+    let mut initial = problem.generate();
+    let initial_score = problem.score(&mut initial);
+    let mut beam = vec![(initial_score, 0, initial)];
+
+    for turn in 0..max_turn {
+        if !timer.tick() {
+            break;
+        }
+        let mut candidates = Vec::new();
+        for (_, id, st) in beam.iter() {
+            for (action, mut next) in problem.expand(st, turn) {
+                let score = problem.score(&mut next);
+                candidates.push((score, *id, action, next));
+            }
+        }
+        if candidates.is_empty() {
+            break;
+        }
+        candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+
+        let mut seen = HashSet::with_capacity(width);
+        let mut next_beam = Vec::with_capacity(width);
+        for (score, parent, action, next) in candidates {
+            if next_beam.len() == width {
+                break;
+            }
+            if !seen.insert(problem.hash(&next)) {
+                continue;
+            }
+            nodes.push(Node {
+                parent,
+                action: Some(action),
+            });
+            next_beam.push((score, nodes.len() - 1, next));
+        }
+        let score = next_beam[0].0;
+        problem.observe(&Record::new(&timer, score, score, None, None));
+        beam = next_beam;
+    }
+
+    let (_, mut id, best) = beam
+        .into_iter()
+        .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+        .unwrap();
+    let mut actions = Vec::new();
+    while id != 0 {
+        actions.push(nodes[id].action.take().unwrap());
+        id = nodes[id].parent;
+    }
+    actions.reverse();
+    (best.state, actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // 0 手目に A (+5) か B, C (+6) を選ぶ。A の後は +10 を、B, C の後は +0 しか選べない
+    struct Trap;
+
+    impl Metaheuristics<(i64, usize)> for Trap {
+        fn evaluate(&mut self, st: &mut StateWrapper<(i64, usize)>) -> f64 {
+            st.state().0 as f64
+        }
+        fn generate(&mut self) -> StateWrapper<(i64, usize)> {
+            StateWrapper::new((0, 0))
+        }
+        fn neighbor(&mut self, st: &StateWrapper<(i64, usize)>) -> StateWrapper<(i64, usize)> {
+            st.clone()
+        }
+    }
+
+    impl BeamSearch<(i64, usize)> for Trap {
+        type Action = char;
+        fn expand(
+            &mut self,
+            st: &StateWrapper<(i64, usize)>,
+            turn: usize,
+        ) -> Vec<(char, StateWrapper<(i64, usize)>)> {
+            let &(sum, last) = st.state();
+            match (turn, last) {
+                (0, _) => vec![
+                    ('A', StateWrapper::new((sum + 5, 1))),
+                    ('B', StateWrapper::new((sum + 6, 2))),
+                    ('C', StateWrapper::new((sum + 6, 2))),
+                ],
+                (1, 1) => vec![('y', StateWrapper::new((sum + 10, 0)))],
+                (1, 2) => vec![('x', StateWrapper::new((sum, 0)))],
+                _ => vec![],
+            }
+        }
+        fn hash(&self, st: &StateWrapper<(i64, usize)>) -> u64 {
+            let &(sum, last) = st.state();
+            (sum as u64) << 32 | last as u64
+        }
+    }
+
+    #[test]
+    fn test_greedy() {
+        let (state, actions) = beam_search(&mut Trap, Duration::from_secs(1), 1, 5);
+        assert_eq!(state, (6, 0));
+        assert_eq!(actions, vec!['B', 'x']);
+    }
+
+    #[test]
+    fn test_beam() {
+        // B と C は同じ状態に至るので重複が除かれ、幅 2 のビームには B と A が残る
+        let (state, actions) = beam_search(&mut Trap, Duration::from_secs(1), 2, 5);
+        assert_eq!(state, (15, 0));
+        assert_eq!(actions, vec!['A', 'y']);
+    }
+
+    #[test]
+    fn test_turn_limit() {
+        let (state, actions) = beam_search(&mut Trap, Duration::from_secs(1), 3, 1);
+        assert_eq!(state, (6, 2));
+        assert_eq!(actions.len(), 1);
+        let (state, actions) = beam_search(&mut Trap, 1, 3, 5);
+        assert_eq!(state, (6, 2));
+        assert_eq!(actions.len(), 1);
+    }
+}
@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+/// 探索を打ち切るまでの実行時間または反復回数
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Budget {
+    Time(Duration),
+    Iterations(usize),
+}
+
+impl Budget {
+    /// k 回に分けて使うときの 1 回分
+    pub fn split(self, k: usize) -> Self {
+        match self {
+            Budget::Time(runtime) => Budget::Time(runtime / k as u32),
+            Budget::Iterations(n) => Budget::Iterations(n / k),
+        }
+    }
+}
+
+impl From<Duration> for Budget {
+    fn from(runtime: Duration) -> Self {
+        Budget::Time(runtime)
+    }
+}
+
+impl From<usize> for Budget {
+    fn from(iterations: usize) -> Self {
+        Budget::Iterations(iterations)
+    }
+}
+
+/// Budget の消費を数える
+#[derive(Clone, Debug)]
+pub struct Timer {
+    budget: Budget,
+    start_time: Instant,
+    elapsed: Duration,
+    iteration: usize,
+}
+
+impl Timer {
+    pub fn new<B: Into<Budget>>(budget: B) -> Self {
+        Self {
+            budget: budget.into(),
+            start_time: Instant::now(),
+            elapsed: Duration::from_secs(0),
+            iteration: 0,
+        }
+    }
+
+    /// 予算が残っていれば反復を 1 回数えて true を返す
+    pub fn tick(&mut self) -> bool {
+        self.elapsed = Instant::now().duration_since(self.start_time);
+        let remains = match self.budget {
+            Budget::Time(runtime) => self.elapsed < runtime,
+            Budget::Iterations(n) => self.iteration < n,
+        };
+        if remains {
+            self.iteration += 1;
+        }
+        remains
+    }
+
+    /// 直前の tick の時点での予算の消費割合 (0 以上 1 未満)
+    pub fn progress(&self) -> f64 {
+        match self.budget {
+            Budget::Time(runtime) => self.elapsed.as_secs_f64() / runtime.as_secs_f64(),
+            Budget::Iterations(n) => self.iteration.saturating_sub(1) as f64 / n as f64,
+        }
+    }
+
+    /// これまでに数えた反復回数
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// 直前の tick の時点での経過時間
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let budget = Budget::from(Duration::from_millis(90)).split(3);
+        assert_eq!(budget, Budget::Time(Duration::from_millis(30)));
+        assert_eq!(Budget::from(10).split(3), Budget::Iterations(3));
+    }
+
+    #[test]
+    fn test_iterations() {
+        let mut timer = Timer::new(4);
+        let mut progress = vec![];
+        while timer.tick() {
+            progress.push(timer.progress());
+        }
+        assert_eq!(progress, vec![0., 0.25, 0.5, 0.75]);
+        assert_eq!(timer.iteration(), 4);
+        assert!(!timer.tick());
+    }
+
+    #[test]
+    fn test_time() {
+        let mut timer = Timer::new(Duration::from_millis(20));
+        while timer.tick() {
+            assert!(timer.progress() < 1.);
+        }
+        assert!(timer.elapsed() >= Duration::from_millis(20));
+        assert!(timer.iteration() > 0);
+    }
+}
@@ -0,0 +1,181 @@
+//! 各座標に上下限のある実数ベクトルの最適化
+
+pub mod cma_es;
+pub mod differential_evolution;
+pub mod nelder_mead;
+
+use crate::heuristics::telemetry::Record;
+use rand::Rng;
+
+pub trait ContinuousProblem {
+    /// 最大化する値。x は bounds の範囲に収まっている
+    fn evaluate(&mut self, x: &[f64]) -> f64;
+    fn bounds(&self) -> &Bounds;
+
+    /// 探索の各反復の終わりに呼ばれる
+    fn observe(&mut self, _record: &Record) {}
+}
+
+/// 各座標の閉区間 [lower, upper]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bounds(Vec<(f64, f64)>);
+
+impl Bounds {
+    pub fn new(bounds: Vec<(f64, f64)>) -> Self {
+        assert!(
+            bounds.iter().all(|&(lower, upper)| lower <= upper),
+            "lower bound must not exceed upper bound"
+        );
+        Self(bounds)
+    }
+
+    /// 全座標で同じ範囲
+    pub fn uniform(dim: usize, lower: f64, upper: f64) -> Self {
+        Self::new(vec![(lower, upper); dim])
+    }
+
+    pub fn dim(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn lower(&self, i: usize) -> f64 {
+        self.0[i].0
+    }
+
+    pub fn upper(&self, i: usize) -> f64 {
+        self.0[i].1
+    }
+
+    pub fn width(&self, i: usize) -> f64 {
+        self.0[i].1 - self.0[i].0
+    }
+
+    pub fn center(&self) -> Vec<f64> {
+        self.0.iter().map(|&(l, u)| (l + u) / 2.).collect()
+    }
+
+    pub fn contains(&self, x: &[f64]) -> bool {
+        x.len() == self.dim() && x.iter().zip(&self.0).all(|(&v, &(l, u))| l <= v && v <= u)
+    }
+
+    /// 範囲外の座標を最も近い端に寄せる
+    pub fn project(&self, x: &mut [f64]) {
+        for (v, &(l, u)) in x.iter_mut().zip(&self.0) {
+            *v = v.max(l).min(u);
+        }
+    }
+
+    /// 各手法の最初に呼ぶ。次元が 0 だったり幅が 0 の座標があると探索できない
+    fn assert_searchable(&self) {
+        assert!(self.dim() > 0, "bounds must have at least one dimension");
+        assert!(
+            self.0.iter().all(|&(lower, upper)| lower < upper),
+            "lower bound must be less than upper bound in every dimension"
+        );
+    }
+
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Vec<f64> {
+        self.0
+            .iter()
+            .map(|&(l, u)| l + (u - l) * rng.gen::<f64>())
+            .collect()
+    }
+}
+
+/// 標準正規分布 (Box-Muller 法)
+fn gaussian<R: Rng>(rng: &mut R) -> f64 {
+    let u = 1. - rng.gen::<f64>();
+    let v = rng.gen::<f64>();
+    (-2. * u.ln()).sqrt() * (2. * std::f64::consts::PI * v).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::random::XorShift;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_bounds() {
+        let bounds = Bounds::new(vec![(0., 1.), (-2., 2.)]);
+        assert_eq!(bounds.dim(), 2);
+        assert_eq!(bounds.center(), vec![0.5, 0.]);
+        assert!((bounds.width(1) - 4.).abs() < 1e-9);
+        let mut x = vec![1.5, -3.];
+        assert!(!bounds.contains(&x));
+        bounds.project(&mut x);
+        assert_eq!(x, vec![1., -2.]);
+        let mut rng = XorShift::seed_from_u64(1);
+        for _ in 0..100 {
+            assert!(bounds.contains(&bounds.sample(&mut rng)));
+        }
+    }
+
+    struct Constant(Bounds);
+
+    impl ContinuousProblem for Constant {
+        fn evaluate(&mut self, _x: &[f64]) -> f64 {
+            0.
+        }
+        fn bounds(&self) -> &Bounds {
+            &self.0
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one dimension")]
+    fn test_differential_evolution_empty() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let config = differential_evolution::DeConfig::default();
+        let mut problem = Constant(Bounds::new(vec![]));
+        differential_evolution::differential_evolution(&mut problem, 10, &config, &mut rng);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one dimension")]
+    fn test_nelder_mead_empty() {
+        let mut problem = Constant(Bounds::new(vec![]));
+        nelder_mead::nelder_mead(&mut problem, &[], 0.1, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one dimension")]
+    fn test_cma_es_empty() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let mut problem = Constant(Bounds::new(vec![]));
+        cma_es::cma_es(&mut problem, &[], 0.3, 10, &mut rng);
+    }
+
+    #[test]
+    #[should_panic(expected = "less than upper bound")]
+    fn test_degenerate_bounds() {
+        let mut problem = Constant(Bounds::new(vec![(0., 1.), (2., 2.)]));
+        nelder_mead::nelder_mead(&mut problem, &[0.5, 2.], 0.1, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "same dimension as bounds")]
+    fn test_nelder_mead_dimension_mismatch() {
+        let mut problem = Constant(Bounds::new(vec![(0., 1.), (0., 1.)]));
+        nelder_mead::nelder_mead(&mut problem, &[0.5], 0.1, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "same dimension as bounds")]
+    fn test_cma_es_dimension_mismatch() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let mut problem = Constant(Bounds::new(vec![(0., 1.)]));
+        cma_es::cma_es(&mut problem, &[0.5, 0.5], 0.3, 10, &mut rng);
+    }
+
+    #[test]
+    fn test_gaussian() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let n = 100000;
+        let xs = (0..n).map(|_| gaussian(&mut rng)).collect::<Vec<_>>();
+        let mean = xs.iter().sum::<f64>() / n as f64;
+        let var = xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n as f64;
+        assert!(mean.abs() < 0.02);
+        assert!((var - 1.).abs() < 0.02);
+    }
+}
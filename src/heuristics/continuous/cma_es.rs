@@ -0,0 +1,176 @@
+use crate::heuristics::{
+    budget::{Budget, Timer},
+    continuous::{gaussian, ContinuousProblem},
+    telemetry::Record,
+};
+use rand::Rng;
+
+/// 共分散行列を対角に限った CMA-ES (sep-CMA-ES)
+/// 初期の標準偏差は各座標の範囲の幅の sigma 倍
+/// 標本は範囲内に射影して評価し、分布の更新には射影前の値を使う
+/// 反復回数の予算は世代数として数える
+pub fn cma_es<T, B, R>(
+    problem: &mut T,
+    initial: &[f64],
+    sigma: f64,
+    budget: B,
+    rng: &mut R,
+) -> Vec<f64>
+where
+    T: ContinuousProblem,
+    B: Into<Budget>,
+    R: Rng,
+{
+    let bounds = problem.bounds().clone();
+    bounds.assert_searchable();
+    let n = bounds.dim();
+    assert_eq!(
+        initial.len(),
+        n,
+        "initial must have the same dimension as bounds"
+    );
+    let nf = n as f64;
+    let lambda = 4 + (3. * nf.ln()) as usize;
+    let mu = lambda / 2;
+    let weights = {
+        let w = (0..mu)
+            .map(|i| ((lambda as f64 + 1.) / 2.).ln() - ((i + 1) as f64).ln())
+            .collect::<Vec<_>>();
+        let sum = w.iter().sum::<f64>();
+        w.into_iter().map(|w| w / sum).collect::<Vec<_>>()
+    };
+    let mueff = 1. / weights.iter().map(|w| w * w).sum::<f64>();
+    let cs = (mueff + 2.) / (nf + mueff + 5.);
+    let ds = 1. + 2. * (((mueff - 1.) / (nf + 1.)).sqrt() - 1.).max(0.) + cs;
+    let cc = (4. + mueff / nf) / (nf + 4. + 2. * mueff / nf);
+    let scale = (nf + 2.) / 3.;
+    let c1 = scale * 2. / ((nf + 1.3) * (nf + 1.3) + mueff);
+    let cmu =
+        (scale * 2. * (mueff - 2. + 1. / mueff) / ((nf + 2.) * (nf + 2.) + mueff)).min(1. - c1);
+    let chi = nf.sqrt() * (1. - 1. / (4. * nf) + 1. / (21. * nf * nf));
+
+    let mut mean = initial.to_vec();
+    let mut step = sigma;
+    let mut variance = (0..n).map(|i| bounds.width(i).powi(2)).collect::<Vec<_>>();
+    let mut ps = vec![0.; n];
+    let mut pc = vec![0.; n];
+    let mut best = initial.to_vec();
+    bounds.project(&mut best);
+    let mut best_score = problem.evaluate(&best);
+    let mut timer = Timer::new(budget);
+
+    while timer.tick() {
+        let mut samples = (0..lambda)
+            .map(|_| {
+                let z = (0..n).map(|_| gaussian(rng)).collect::<Vec<_>>();
+                let mut x = (0..n)
+                    .map(|i| mean[i] + step * variance[i].sqrt() * z[i])
+                    .collect::<Vec<_>>();
+                bounds.project(&mut x);
+                let score = problem.evaluate(&x);
+                (score, z, x)
+            })
+            .collect::<Vec<_>>();
+        samples.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        if samples[0].0 > best_score {
+            best_score = samples[0].0;
+            best = samples[0].2.clone();
+        }
+
+        let zw = (0..n)
+            .map(|i| (0..mu).map(|k| weights[k] * samples[k].1[i]).sum::<f64>())
+            .collect::<Vec<_>>();
+        let yw = (0..n)
+            .map(|i| variance[i].sqrt() * zw[i])
+            .collect::<Vec<_>>();
+        for i in 0..n {
+            mean[i] += step * yw[i];
+        }
+
+        let a = (cs * (2. - cs) * mueff).sqrt();
+        for i in 0..n {
+            ps[i] = (1. - cs) * ps[i] + a * zw[i];
+        }
+        let norm = ps.iter().map(|p| p * p).sum::<f64>().sqrt();
+        let decay = 1. - (1. - cs).powi(2 * timer.iteration() as i32);
+        let hs = norm / decay.sqrt() < (1.4 + 2. / (nf + 1.)) * chi;
+        let b = if hs {
+            (cc * (2. - cc) * mueff).sqrt()
+        } else {
+            0.
+        };
+        for i in 0..n {
+            pc[i] = (1. - cc) * pc[i] + b * yw[i];
+            let rank_mu = (0..mu)
+                .map(|k| weights[k] * samples[k].1[i] * samples[k].1[i])
+                .sum::<f64>()
+                * variance[i];
+            let correction = if hs { 0. } else { cc * (2. - cc) * variance[i] };
+            variance[i] =
+                (1. - c1 - cmu) * variance[i] + c1 * (pc[i] * pc[i] + correction) + cmu * rank_mu;
+        }
+        step *= ((cs / ds) * (norm / chi - 1.)).exp();
+
+        let record = Record::new(&timer, samples[0].0, best_score, None, None);
+        problem.observe(&record);
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::continuous::Bounds;
+    use crate::utils::random::XorShift;
+    use rand::SeedableRng;
+
+    // 座標ごとに尺度の違う楕円体 -Σ 10^i (x_i - 1)^2
+    struct Ellipsoid(Bounds);
+
+    impl ContinuousProblem for Ellipsoid {
+        fn evaluate(&mut self, x: &[f64]) -> f64 {
+            -x.iter()
+                .enumerate()
+                .map(|(i, v)| 10f64.powi(i as i32) * (v - 1.) * (v - 1.))
+                .sum::<f64>()
+        }
+        fn bounds(&self) -> &Bounds {
+            &self.0
+        }
+    }
+
+    // Rosenbrock 関数の符号を反転したもの。最適解は (1, 1)
+    struct Rosenbrock(Bounds);
+
+    impl ContinuousProblem for Rosenbrock {
+        fn evaluate(&mut self, x: &[f64]) -> f64 {
+            -(100. * (x[1] - x[0] * x[0]).powi(2) + (1. - x[0]).powi(2))
+        }
+        fn bounds(&self) -> &Bounds {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn test_ellipsoid() {
+        let mut problem = Ellipsoid(Bounds::uniform(6, -5., 5.));
+        let mut rng = XorShift::seed_from_u64(1);
+        let initial = problem.0.center();
+        let x = cma_es(&mut problem, &initial, 0.3, 1000, &mut rng);
+        for v in x {
+            assert!((v - 1.).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_rosenbrock() {
+        let run = |seed| {
+            let mut problem = Rosenbrock(Bounds::uniform(2, -5., 5.));
+            let mut rng = XorShift::seed_from_u64(seed);
+            cma_es(&mut problem, &[-1.2, 1.], 0.1, 2000, &mut rng)
+        };
+        let x = run(1);
+        assert!((x[0] - 1.).abs() < 1e-2 && (x[1] - 1.).abs() < 1e-2);
+        assert_eq!(run(2), run(2));
+    }
+}
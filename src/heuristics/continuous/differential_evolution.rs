@@ -0,0 +1,152 @@
+use crate::heuristics::{
+    budget::{Budget, Timer},
+    continuous::ContinuousProblem,
+    telemetry::Record,
+};
+use rand::Rng;
+
+#[derive(Clone, Debug)]
+pub struct DeConfig {
+    pub population: usize,
+    /// 差分ベクトルに掛ける係数 F
+    pub weight: f64,
+    /// 各座標を変異ベクトルから取る確率 CR
+    pub crossover: f64,
+}
+
+impl Default for DeConfig {
+    fn default() -> Self {
+        Self {
+            population: 30,
+            weight: 0.5,
+            crossover: 0.9,
+        }
+    }
+}
+
+/// DE/rand/1/bin。範囲外に出た座標は親と境界の中点に戻す
+/// 反復回数の予算は世代数として数える
+pub fn differential_evolution<T, B, R>(
+    problem: &mut T,
+    budget: B,
+    config: &DeConfig,
+    rng: &mut R,
+) -> Vec<f64>
+where
+    T: ContinuousProblem,
+    B: Into<Budget>,
+    R: Rng,
+{
+    let np = config.population;
+    assert!(np >= 4, "population must be at least 4");
+    let bounds = problem.bounds().clone();
+    bounds.assert_searchable();
+    let dim = bounds.dim();
+    let mut population = (0..np).map(|_| bounds.sample(rng)).collect::<Vec<_>>();
+    let mut scores = population
+        .iter()
+        .map(|x| problem.evaluate(x))
+        .collect::<Vec<_>>();
+    let mut best = 0;
+    for i in 1..np {
+        if scores[i] > scores[best] {
+            best = i;
+        }
+    }
+    let mut accepted = 0;
+    let mut timer = Timer::new(budget);
+
+    while timer.tick() {
+        for i in 0..np {
+            let mut pick = || loop {
+                let j = rng.gen_range(0..np);
+                if j != i {
+                    return j;
+                }
+            };
+            let (a, b, c) = loop {
+                let (a, b, c) = (pick(), pick(), pick());
+                if a != b && b != c && c != a {
+                    break (a, b, c);
+                }
+            };
+            let forced = rng.gen_range(0..dim);
+            let mut trial = population[i].clone();
+            for (k, t) in trial.iter_mut().enumerate() {
+                if k != forced && !rng.gen_bool(config.crossover) {
+                    continue;
+                }
+                let v = population[a][k] + config.weight * (population[b][k] - population[c][k]);
+                *t = if v < bounds.lower(k) {
+                    (population[i][k] + bounds.lower(k)) / 2.
+                } else if v > bounds.upper(k) {
+                    (population[i][k] + bounds.upper(k)) / 2.
+                } else {
+                    v
+                };
+            }
+            let score = problem.evaluate(&trial);
+            if score >= scores[i] {
+                accepted += 1;
+                population[i] = trial;
+                scores[i] = score;
+                if score > scores[best] {
+                    best = i;
+                }
+            }
+        }
+        let ratio = accepted as f64 / (timer.iteration() * np) as f64;
+        let mut record = Record::new(&timer, scores[best], scores[best], None, None);
+        record.acceptance_ratio = Some(ratio);
+        problem.observe(&record);
+    }
+    population.swap_remove(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::continuous::Bounds;
+    use crate::utils::random::XorShift;
+    use rand::SeedableRng;
+
+    // -Σ (x_i - c_i)^2 を最大化する
+    struct Sphere {
+        bounds: Bounds,
+        center: Vec<f64>,
+    }
+
+    impl ContinuousProblem for Sphere {
+        fn evaluate(&mut self, x: &[f64]) -> f64 {
+            -x.iter()
+                .zip(&self.center)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f64>()
+        }
+        fn bounds(&self) -> &Bounds {
+            &self.bounds
+        }
+    }
+
+    #[test]
+    fn test_sphere() {
+        let mut problem = Sphere {
+            bounds: Bounds::uniform(5, -10., 10.),
+            center: vec![1., -2., 3., -4., 5.],
+        };
+        let mut rng = XorShift::seed_from_u64(1);
+        let x = differential_evolution(&mut problem, 300, &DeConfig::default(), &mut rng);
+        for (a, b) in x.iter().zip(&problem.center) {
+            assert!((a - b).abs() < 1e-3);
+        }
+
+        // 最適解が範囲外なら境界に張り付く
+        let mut problem = Sphere {
+            bounds: Bounds::new(vec![(0., 1.), (0., 1.)]),
+            center: vec![2., 0.5],
+        };
+        let x = differential_evolution(&mut problem, 200, &DeConfig::default(), &mut rng);
+        assert!(problem.bounds.contains(&x));
+        assert!((x[0] - 1.).abs() < 1e-3 && (x[1] - 0.5).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::heuristics::{
+    budget::{Budget, Timer},
+    continuous::ContinuousProblem,
+    telemetry::Record,
+};
+
+/// initial と、各座標を範囲の幅の step 倍だけずらした点を頂点とする単体から始める
+/// 頂点は範囲内に射影してから評価する。単体が十分小さくなったら打ち切る
+pub fn nelder_mead<T: ContinuousProblem, B: Into<Budget>>(
+    problem: &mut T,
+    initial: &[f64],
+    step: f64,
+    budget: B,
+) -> Vec<f64> {
+    let bounds = problem.bounds().clone();
+    bounds.assert_searchable();
+    let dim = bounds.dim();
+    assert_eq!(
+        initial.len(),
+        dim,
+        "initial must have the same dimension as bounds"
+    );
+    let evaluate = |problem: &mut T, mut x: Vec<f64>| {
+        bounds.project(&mut x);
+        let score = problem.evaluate(&x);
+        (score, x)
+    };
+    let mut simplex = vec![evaluate(problem, initial.to_vec())];
+    for i in 0..dim {
+        let mut x = initial.to_vec();
+        let d = step * bounds.width(i);
+        x[i] += if x[i] + d <= bounds.upper(i) { d } else { -d };
+        simplex.push(evaluate(problem, x));
+    }
+    let mut timer = Timer::new(budget);
+
+    while timer.tick() {
+        simplex.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        let size = simplex[1..]
+            .iter()
+            .flat_map(|(_, x)| x.iter().zip(&simplex[0].1).map(|(a, b)| (a - b).abs()))
+            .fold(0., f64::max);
+        if size < 1e-12 {
+            break;
+        }
+        let centroid = (0..dim)
+            .map(|k| simplex[..dim].iter().map(|(_, x)| x[k]).sum::<f64>() / dim as f64)
+            .collect::<Vec<_>>();
+        let (worst_score, worst) = simplex[dim].clone();
+        let toward = |t: f64| {
+            centroid
+                .iter()
+                .zip(&worst)
+                .map(|(c, w)| c + t * (c - w))
+                .collect::<Vec<_>>()
+        };
+
+        let reflected = evaluate(problem, toward(1.));
+        if reflected.0 > simplex[0].0 {
+            let expanded = evaluate(problem, toward(2.));
+            simplex[dim] = if expanded.0 > reflected.0 {
+                expanded
+            } else {
+                reflected
+            };
+        } else if reflected.0 > simplex[dim - 1].0 {
+            simplex[dim] = reflected;
+        } else {
+            let contracted = if reflected.0 > worst_score {
+                evaluate(problem, toward(0.5))
+            } else {
+                evaluate(problem, toward(-0.5))
+            };
+            if contracted.0 > reflected.0.max(worst_score) {
+                simplex[dim] = contracted;
+            } else {
+                // 最良の頂点に向かって縮める
+                let best = simplex[0].1.clone();
+                for v in simplex.iter_mut().skip(1) {
+                    let x = v.1.iter().zip(&best).map(|(a, b)| (a + b) / 2.).collect();
+                    *v = evaluate(problem, x);
+                }
+            }
+        }
+
+        let best_score = simplex.iter().map(|v| v.0).fold(simplex[0].0, f64::max);
+        let current = simplex.iter().map(|v| v.0).sum::<f64>() / (dim + 1) as f64;
+        problem.observe(&Record::new(&timer, current, best_score, None, None));
+    }
+    simplex
+        .into_iter()
+        .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+        .unwrap()
+        .1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::continuous::Bounds;
+
+    // Rosenbrock 関数の符号を反転したもの。最適解は (1, 1)
+    struct Rosenbrock(Bounds);
+
+    impl ContinuousProblem for Rosenbrock {
+        fn evaluate(&mut self, x: &[f64]) -> f64 {
+            -(100. * (x[1] - x[0] * x[0]).powi(2) + (1. - x[0]).powi(2))
+        }
+        fn bounds(&self) -> &Bounds {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn test_rosenbrock() {
+        let mut problem = Rosenbrock(Bounds::uniform(2, -5., 5.));
+        let x = nelder_mead(&mut problem, &[-1.2, 1.], 0.05, 2000);
+        assert!((x[0] - 1.).abs() < 1e-4 && (x[1] - 1.).abs() < 1e-4);
+
+        // 最適解を含まない範囲では境界上の最良点に近づく
+        let mut problem = Rosenbrock(Bounds::new(vec![(-2., 0.5), (-2., 2.)]));
+        let x = nelder_mead(&mut problem, &[-1., 1.], 0.1, 2000);
+        assert!(problem.0.contains(&x));
+        assert!((x[0] - 0.5).abs() < 1e-3 && (x[1] - 0.25).abs() < 1e-3);
+    }
+}
@@ -1,66 +1,243 @@
-use crate::heuristics::{Metaheuristics, State, StateWrapper};
-use rand::{thread_rng, Rng};
-use std::time::{Duration, Instant};
+use crate::heuristics::{
+    budget::{Budget, Timer},
+    telemetry::Record,
+    Metaheuristics, State, StateWrapper,
+};
+use rand::Rng;
 
-pub fn genetics<S: State, T: Metaheuristics<S>>(problem: &mut T, runtime: Duration) -> S {
-    let mut rng = thread_rng();
-    let gen_size = 30;
-    let p_mutation = 0.05;
-    let p_cross = 0.75;
+pub trait GeneticProblem<S: State>: Metaheuristics<S> {
+    fn mutate(&mut self, st: &StateWrapper<S>) -> StateWrapper<S>;
+    fn crossover(
+        &mut self,
+        s: &StateWrapper<S>,
+        t: &StateWrapper<S>,
+    ) -> (StateWrapper<S>, StateWrapper<S>);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Selection {
+    /// スコアの最小値を 0 に合わせた値に比例する確率で選ぶ
+    Roulette,
+    /// k 個を一様に選び、その中で最良のものを選ぶ
+    Tournament(usize),
+    /// 順位が i 番目 (0-indexed) のものを population - i に比例する確率で選ぶ
+    Rank,
+}
+
+#[derive(Clone, Debug)]
+pub struct GeneticConfig {
+    pub population: usize,
+    pub p_mutation: f64,
+    pub p_cross: f64,
+    /// そのまま次の世代に残す上位の個体数
+    pub elite: usize,
+    pub selection: Selection,
+}
+
+impl Default for GeneticConfig {
+    fn default() -> Self {
+        Self {
+            population: 30,
+            p_mutation: 0.05,
+            p_cross: 0.75,
+            elite: 1,
+            selection: Selection::Roulette,
+        }
+    }
+}
+
+/// scores は降順
+fn select<R: Rng>(rng: &mut R, selection: Selection, scores: &[f64]) -> usize {
+    let n = scores.len();
+    match selection {
+        Selection::Roulette => {
+            let min = scores[n - 1];
+            let sum: f64 = scores.iter().map(|&x| x - min).sum();
+            if sum <= 0. {
+                return rng.gen_range(0..n);
+            }
+            let mut r = rng.gen::<f64>() * sum;
+            for (i, &x) in scores.iter().enumerate() {
+                r -= x - min;
+                if r < 0. {
+                    return i;
+                }
+            }
+            n - 1
+        }
+        Selection::Tournament(k) => (0..k.max(1)).map(|_| rng.gen_range(0..n)).min().unwrap(),
+        Selection::Rank => {
+            let mut r = rng.gen_range(0..n * (n + 1) / 2);
+            for i in 0..n {
+                if r < n - i {
+                    return i;
+                }
+                r -= n - i;
+            }
+            n - 1
+        }
+    }
+}
+
+/// これまでに現れた中で最良の個体を返す
+/// 反復回数の予算は世代数として数える
+pub fn genetics<S: State, T: GeneticProblem<S>, B: Into<Budget>, R: Rng>(
+    problem: &mut T,
+    budget: B,
+    config: &GeneticConfig,
+    rng: &mut R,
+) -> S {
+    assert!(config.population > 0, "population must be positive");
+    let gen_size = config.population;
     let mut current_generation = (0..gen_size)
         .map(|_| problem.generate())
         .collect::<Vec<_>>();
-    let start_time = Instant::now();
+    let mut best: Option<(f64, StateWrapper<S>)> = None;
+    let mut timer = Timer::new(budget);
 
-    while Instant::now().duration_since(start_time) < runtime {
-        let scores = current_generation
-            .iter_mut()
-            .map(|s| problem.evaluate(s))
-            .scan(0., |sum, x| {
-                *sum += x;
-                Some(*sum)
-            })
-            .collect::<Vec<f64>>();
-        let mut next_generation: Vec<StateWrapper<S>> = Vec::with_capacity(gen_size * 2);
-        for _ in 0..gen_size {
-            let rand_idx = rng.gen_range(0f64..*scores.last().unwrap());
-            let idx = scores
-                .binary_search_by(|x| x.partial_cmp(&rand_idx).unwrap())
-                .unwrap();
+    loop {
+        let mut generation = current_generation
+            .into_iter()
+            .map(|mut s| (problem.score(&mut s), s))
+            .collect::<Vec<_>>();
+        generation.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        let improved = match &best {
+            Some(b) => generation[0].0 > b.0,
+            None => true,
+        };
+        if improved {
+            best = Some(generation[0].clone());
+        }
+        if !timer.tick() {
+            break;
+        }
+        let best_score = best.as_ref().unwrap().0;
+        problem.observe(&Record::new(
+            &timer,
+            generation[0].0,
+            best_score,
+            None,
+            None,
+        ));
+        let scores = generation.iter().map(|x| x.0).collect::<Vec<f64>>();
+
+        let mut next_generation: Vec<StateWrapper<S>> = generation
+            .iter()
+            .take(config.elite)
+            .map(|x| x.1.clone())
+            .collect();
+        while next_generation.len() < gen_size {
+            let idx = select(rng, config.selection, &scores);
             let gen_type = rng.gen::<f64>();
-            if gen_type < p_mutation {
-                next_generation.push(mutate(&current_generation[idx]))
-            } else if gen_type < p_mutation + p_cross {
-                let rand_idx_2 = rng.gen_range(0f64..*scores.last().unwrap());
-                let idx_2 = scores
-                    .binary_search_by(|x| x.partial_cmp(&rand_idx_2).unwrap())
-                    .unwrap();
+            if gen_type < config.p_mutation {
+                next_generation.push(problem.mutate(&generation[idx].1));
+            } else if gen_type < config.p_mutation + config.p_cross {
+                let idx_2 = select(rng, config.selection, &scores);
                 if idx == idx_2 {
-                    next_generation.push(current_generation[idx].clone());
+                    next_generation.push(generation[idx].1.clone());
                 } else {
-                    let (x1, x2) = cross(&current_generation[idx], &current_generation[idx_2]);
+                    let (x1, x2) = problem.crossover(&generation[idx].1, &generation[idx_2].1);
                     next_generation.push(x1);
                     next_generation.push(x2);
                 }
             } else {
-                next_generation.push(current_generation[idx].clone());
+                next_generation.push(generation[idx].1.clone());
             }
         }
-        let mut scores = current_generation
-            .iter_mut()
-            .map(|s| problem.evaluate(s))
-            .collect::<Vec<f64>>();
-        scores.sort_by(|a, b| a.partial_cmp(&b).unwrap());
-        scores.reverse();
+        next_generation.truncate(gen_size);
         current_generation = next_generation;
     }
-    current_generation[0].clone().state
+    best.unwrap().1.state
 }
 
-fn mutate<S: State>(s: &StateWrapper<S>) -> StateWrapper<S> {
-    s.clone()
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::random::XorShift;
+    use rand::SeedableRng;
+    use std::time::Duration;
+
+    // 長さ 20 のビット列の 1 の個数を最大化する
+    struct OneMax(XorShift);
+
+    impl Metaheuristics<Vec<bool>> for OneMax {
+        fn evaluate(&mut self, st: &mut StateWrapper<Vec<bool>>) -> f64 {
+            st.state().iter().filter(|&&b| b).count() as f64
+        }
+        fn generate(&mut self) -> StateWrapper<Vec<bool>> {
+            let rng = &mut self.0;
+            StateWrapper::new((0..20).map(|_| rng.gen_bool(0.5)).collect())
+        }
+        fn neighbor(&mut self, st: &StateWrapper<Vec<bool>>) -> StateWrapper<Vec<bool>> {
+            self.mutate(st)
+        }
+    }
+
+    impl GeneticProblem<Vec<bool>> for OneMax {
+        fn mutate(&mut self, st: &StateWrapper<Vec<bool>>) -> StateWrapper<Vec<bool>> {
+            let mut v = st.state().clone();
+            let i = self.0.gen_range(0..v.len());
+            v[i] = !v[i];
+            StateWrapper::new(v)
+        }
+        fn crossover(
+            &mut self,
+            s: &StateWrapper<Vec<bool>>,
+            t: &StateWrapper<Vec<bool>>,
+        ) -> (StateWrapper<Vec<bool>>, StateWrapper<Vec<bool>>) {
+            let k = self.0.gen_range(0..s.state().len());
+            let (s, t) = (s.state(), t.state());
+            let x = s[..k].iter().chain(&t[k..]).cloned().collect();
+            let y = t[..k].iter().chain(&s[k..]).cloned().collect();
+            (StateWrapper::new(x), StateWrapper::new(y))
+        }
+    }
+
+    #[test]
+    fn test_select() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let scores = [3., 2., 1., 1.];
+        for _ in 0..100 {
+            assert!(select(&mut rng, Selection::Roulette, &scores) < 2);
+            assert!(select(&mut rng, Selection::Rank, &scores) < 4);
+            assert_eq!(select(&mut rng, Selection::Tournament(100), &scores), 0);
+        }
+        assert!(select(&mut rng, Selection::Roulette, &[1., 1.]) < 2);
+    }
 
-fn cross<S: State>(s: &StateWrapper<S>, t: &StateWrapper<S>) -> (StateWrapper<S>, StateWrapper<S>) {
-    (s.clone(), t.clone())
+    #[test]
+    fn test_one_max() {
+        for &selection in &[
+            Selection::Roulette,
+            Selection::Tournament(3),
+            Selection::Rank,
+        ] {
+            let config = GeneticConfig {
+                p_mutation: 0.3,
+                p_cross: 0.6,
+                selection,
+                ..GeneticConfig::default()
+            };
+            let mut problem = OneMax(XorShift::seed_from_u64(1));
+            let mut rng = XorShift::seed_from_u64(2);
+            let best = genetics(&mut problem, 300, &config, &mut rng);
+            assert!(best.iter().filter(|&&b| b).count() >= 18);
+        }
+        let mut problem = OneMax(XorShift::seed_from_u64(3));
+        let mut rng = XorShift::seed_from_u64(4);
+        let config = GeneticConfig::default();
+        let best = genetics(&mut problem, Duration::from_millis(50), &config, &mut rng);
+        assert_eq!(best.len(), 20);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let run = |seed| {
+            let mut problem = OneMax(XorShift::seed_from_u64(seed));
+            let mut rng = XorShift::seed_from_u64(seed);
+            genetics(&mut problem, 5, &GeneticConfig::default(), &mut rng)
+        };
+        assert_eq!(run(7), run(7));
+        assert_eq!(run(8), run(8));
+    }
 }
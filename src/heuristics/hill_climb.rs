@@ -1,46 +1,243 @@
-use crate::heuristics::{Metaheuristics, State};
-use rand::{thread_rng, Rng};
-use std::time::{Duration, Instant};
+use crate::heuristics::{
+    budget::{Budget, Timer},
+    telemetry::Record,
+    Metaheuristics, MoveMetaheuristics, State, StateWrapper,
+};
+use rand::Rng;
 
-pub fn hill_climb<S: State, T: Metaheuristics<S>>(problem: &mut T, runtime: Duration) -> S {
-    let mut best_candidate = problem.generate();
-    let start_time = Instant::now();
+pub fn hill_climb<S: State, T: Metaheuristics<S>, B: Into<Budget>>(
+    problem: &mut T,
+    budget: B,
+) -> S {
+    let initial = problem.generate();
+    hill_climb_from(problem, initial, budget).state
+}
 
-    while Instant::now().duration_since(start_time) < runtime {
+/// initial から始めて、最良の状態を返す
+pub fn hill_climb_from<S: State, T: Metaheuristics<S>, B: Into<Budget>>(
+    problem: &mut T,
+    initial: StateWrapper<S>,
+    budget: B,
+) -> StateWrapper<S> {
+    let mut best_candidate = initial;
+    let mut accepted = 0;
+    let mut timer = Timer::new(budget);
+
+    while timer.tick() {
         let mut next_candidate = problem.neighbor(&best_candidate);
 
-        if problem.evaluate(&mut next_candidate) > problem.evaluate(&mut best_candidate) {
+        if problem.score(&mut next_candidate) > problem.score(&mut best_candidate) {
+            accepted += 1;
             best_candidate = next_candidate;
         }
+        let best_score = problem.score(&mut best_candidate);
+        let record = Record::new(&timer, best_score, best_score, None, Some(accepted));
+        problem.observe(&record);
     }
 
-    best_candidate.state
+    best_candidate
 }
 
-pub fn hill_climb_retry<S: State, T: Metaheuristics<S>>(
+pub fn hill_climb_retry<S: State, T: Metaheuristics<S>, B: Into<Budget>, R: Rng>(
     problem: &mut T,
-    runtime: Duration,
+    budget: B,
     probability: f64,
+    rng: &mut R,
 ) -> S {
-    let mut rng = thread_rng();
     let mut current_candidate = problem.generate();
     let mut best_candidate = current_candidate.clone();
-    let start_time = Instant::now();
+    let mut accepted = 0;
+    let mut timer = Timer::new(budget);
 
-    while Instant::now().duration_since(start_time) < runtime {
-        if probability > rng.gen() {
+    while timer.tick() {
+        if probability > rng.gen::<f64>() {
             current_candidate = problem.generate();
-            continue;
+        } else {
+            let mut next_candidate = problem.neighbor(&current_candidate);
+
+            if problem.score(&mut next_candidate) > problem.score(&mut current_candidate) {
+                accepted += 1;
+                current_candidate = next_candidate;
+                if problem.score(&mut current_candidate) > problem.score(&mut best_candidate) {
+                    best_candidate = current_candidate.clone();
+                }
+            }
+        }
+        let record = Record::new(
+            &timer,
+            problem.score(&mut current_candidate),
+            problem.score(&mut best_candidate),
+            None,
+            Some(accepted),
+        );
+        problem.observe(&record);
+    }
+    best_candidate.state
+}
+
+/// hill_climb を MoveMetaheuristics の差分で行う
+pub fn hill_climb_delta<S: State, T: MoveMetaheuristics<S>, B: Into<Budget>>(
+    problem: &mut T,
+    budget: B,
+) -> S {
+    let mut best_candidate = problem.generate();
+    let mut best_score = problem.score(&mut best_candidate);
+    let mut accepted = 0;
+    let mut timer = Timer::new(budget);
+
+    while timer.tick() {
+        let mv = problem.propose(&best_candidate);
+        let delta = problem.delta(&mut best_candidate, &mv);
+
+        if delta > 0. {
+            accepted += 1;
+            problem.apply(&mut best_candidate, &mv);
+            best_score += delta;
+            best_candidate.set_score(best_score);
         }
+        let record = Record::new(&timer, best_score, best_score, None, Some(accepted));
+        problem.observe(&record);
+    }
+
+    best_candidate.state
+}
+
+/// hill_climb_retry を MoveMetaheuristics の差分で行う
+/// 複製は最良解を更新したときのみ行う
+pub fn hill_climb_retry_delta<S: State, T: MoveMetaheuristics<S>, B: Into<Budget>, R: Rng>(
+    problem: &mut T,
+    budget: B,
+    probability: f64,
+    rng: &mut R,
+) -> S {
+    let mut current_candidate = problem.generate();
+    let mut current_score = problem.score(&mut current_candidate);
+    let mut best_candidate = current_candidate.clone();
+    let mut best_score = current_score;
+    let mut accepted = 0;
+    let mut timer = Timer::new(budget);
 
-        let mut next_candidate = problem.neighbor(&current_candidate);
+    while timer.tick() {
+        if probability > rng.gen::<f64>() {
+            current_candidate = problem.generate();
+            current_score = problem.score(&mut current_candidate);
+        } else {
+            let mv = problem.propose(&current_candidate);
+            let delta = problem.delta(&mut current_candidate, &mv);
 
-        if problem.evaluate(&mut next_candidate) > problem.evaluate(&mut current_candidate) {
-            current_candidate = next_candidate;
-            if problem.evaluate(&mut current_candidate) > problem.evaluate(&mut best_candidate) {
-                best_candidate = current_candidate.clone();
+            if delta > 0. {
+                accepted += 1;
+                problem.apply(&mut current_candidate, &mv);
+                current_score += delta;
+                current_candidate.set_score(current_score);
+                if current_score > best_score {
+                    best_score = current_score;
+                    best_candidate = current_candidate.clone();
+                }
             }
         }
+        let record = Record::new(&timer, current_score, best_score, None, Some(accepted));
+        problem.observe(&record);
     }
     best_candidate.state
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::random::XorShift;
+    use rand::SeedableRng;
+    use std::time::Duration;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    // 長さ 30 のビット列の 1 の個数を最大化する
+    struct OneMax {
+        evaluated: usize,
+        rng: XorShift,
+    }
+
+    impl OneMax {
+        fn new(seed: u64) -> Self {
+            OneMax {
+                evaluated: 0,
+                rng: XorShift::seed_from_u64(seed),
+            }
+        }
+    }
+
+    impl Metaheuristics<Vec<bool>> for OneMax {
+        fn evaluate(&mut self, st: &mut StateWrapper<Vec<bool>>) -> f64 {
+            self.evaluated += 1;
+            st.state().iter().filter(|&&b| b).count() as f64
+        }
+        fn generate(&mut self) -> StateWrapper<Vec<bool>> {
+            StateWrapper::new(vec![false; 30])
+        }
+        fn neighbor(&mut self, st: &StateWrapper<Vec<bool>>) -> StateWrapper<Vec<bool>> {
+            let mut next = st.clone();
+            let i = self.propose(st);
+            self.apply(&mut next, &i);
+            next
+        }
+    }
+
+    impl MoveMetaheuristics<Vec<bool>> for OneMax {
+        type Move = usize;
+        fn propose(&mut self, st: &StateWrapper<Vec<bool>>) -> usize {
+            self.rng.gen_range(0..st.state().len())
+        }
+        fn apply(&mut self, st: &mut StateWrapper<Vec<bool>>, &i: &usize) {
+            st.state_mut()[i] ^= true;
+        }
+        fn rollback(&mut self, st: &mut StateWrapper<Vec<bool>>, &i: &usize) {
+            st.state_mut()[i] ^= true;
+        }
+    }
+
+    #[test]
+    fn test_score_cache() {
+        let mut problem = OneMax::new(0);
+        let mut st = problem.generate();
+        assert!(approx(problem.score(&mut st), 0.));
+        assert!(approx(problem.score(&mut st), 0.));
+        assert_eq!(problem.evaluated, 1);
+        problem.apply(&mut st, &3);
+        assert!(approx(problem.score(&mut st), 1.));
+        assert_eq!(problem.evaluated, 2);
+        assert!(approx(problem.delta(&mut st, &4), 1.));
+        assert!(approx(problem.delta(&mut st, &3), -1.));
+        assert!(approx(problem.score(&mut st), 1.));
+        assert_eq!(st.state().iter().filter(|&&b| b).count(), 1);
+    }
+
+    #[test]
+    fn test_hill_climb() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let all = vec![true; 30];
+        assert_eq!(hill_climb(&mut OneMax::new(1), 3000), all);
+        assert_eq!(hill_climb_delta(&mut OneMax::new(2), 3000), all);
+        let runtime = Duration::from_millis(50);
+        assert_eq!(
+            hill_climb_retry(&mut OneMax::new(3), runtime, 0.001, &mut rng),
+            all
+        );
+        assert_eq!(
+            hill_climb_retry_delta(&mut OneMax::new(4), 30000, 0.001, &mut rng),
+            all
+        );
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let run = |seed| {
+            let mut rng = XorShift::seed_from_u64(seed);
+            let mut problem = OneMax::new(seed);
+            hill_climb_retry_delta(&mut problem, 100, 0.01, &mut rng)
+        };
+        assert_eq!(run(5), run(5));
+        assert_ne!(run(5), vec![true; 30]);
+    }
+}
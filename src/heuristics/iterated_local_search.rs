@@ -0,0 +1,181 @@
+use crate::heuristics::{
+    budget::{Budget, Timer},
+    telemetry::Record,
+    Metaheuristics, State, StateWrapper,
+};
+
+pub trait IteratedLocalSearch<S: State>: Metaheuristics<S> {
+    /// 局所解から抜け出すために st を大きく動かす
+    fn kick(&mut self, st: &StateWrapper<S>) -> StateWrapper<S>;
+}
+
+/// kick してから局所探索で得た解を、次の探索の起点にするかどうか
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IlsAcceptance {
+    /// 起点以上のスコアなら移る
+    Better,
+    /// 常に移る
+    RandomWalk,
+    /// Better と同様に移り、k 回続けて最良解が更新されなければ generate() からやり直す
+    RestartAfter(usize),
+}
+
+/// neighbor が連続 patience 回改善しなくなるまで山登りする
+pub fn local_optimum<S: State, T: Metaheuristics<S>>(
+    problem: &mut T,
+    initial: StateWrapper<S>,
+    patience: usize,
+) -> StateWrapper<S> {
+    let mut current_candidate = initial;
+    let mut stall = 0;
+    while stall < patience {
+        let mut next_candidate = problem.neighbor(&current_candidate);
+        if problem.score(&mut next_candidate) > problem.score(&mut current_candidate) {
+            current_candidate = next_candidate;
+            stall = 0;
+        } else {
+            stall += 1;
+        }
+    }
+    current_candidate
+}
+
+/// local_search(problem, st) は st から局所解を探して返す (local_optimum や hill_climb_from など)
+/// 反復回数の予算は kick の回数として数える
+pub fn iterated_local_search<S, T, F, B>(
+    problem: &mut T,
+    budget: B,
+    acceptance: IlsAcceptance,
+    mut local_search: F,
+) -> S
+where
+    S: State,
+    T: IteratedLocalSearch<S>,
+    F: FnMut(&mut T, StateWrapper<S>) -> StateWrapper<S>,
+    B: Into<Budget>,
+{
+    let initial = problem.generate();
+    let mut current_candidate = local_search(problem, initial);
+    let mut current_score = problem.score(&mut current_candidate);
+    let mut best_candidate = current_candidate.clone();
+    let mut best_score = current_score;
+    let mut accepted = 0;
+    let mut stall = 0;
+    let mut timer = Timer::new(budget);
+
+    while timer.tick() {
+        let kicked = problem.kick(&current_candidate);
+        let mut next_candidate = local_search(problem, kicked);
+        let next_score = problem.score(&mut next_candidate);
+
+        if acceptance == IlsAcceptance::RandomWalk || next_score >= current_score {
+            accepted += 1;
+            current_candidate = next_candidate;
+            current_score = next_score;
+        }
+        if current_score > best_score {
+            best_score = current_score;
+            best_candidate = current_candidate.clone();
+            stall = 0;
+        } else {
+            stall += 1;
+        }
+        if let IlsAcceptance::RestartAfter(k) = acceptance {
+            if stall >= k {
+                let initial = problem.generate();
+                current_candidate = local_search(problem, initial);
+                current_score = problem.score(&mut current_candidate);
+                stall = 0;
+                if current_score > best_score {
+                    best_score = current_score;
+                    best_candidate = current_candidate.clone();
+                }
+            }
+        }
+        let record = Record::new(&timer, current_score, best_score, None, Some(accepted));
+        problem.observe(&record);
+    }
+    best_candidate.state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::hill_climb::hill_climb_from;
+    use crate::heuristics::test_problems::{valley, valley_neighbor};
+    use crate::utils::random::XorShift;
+    use rand::{Rng, SeedableRng};
+
+    // test_problems::valley の上を 1 ずつ動き、kick では最大 kick だけ跳ぶ
+    // 最初の generate() だけ start を返し、以降は一様に選ぶ
+    struct Valley {
+        rng: XorShift,
+        start: Option<i64>,
+        kick: i64,
+    }
+
+    impl Valley {
+        fn new(start: Option<i64>, kick: i64) -> Self {
+            Self {
+                rng: XorShift::seed_from_u64(1),
+                start,
+                kick,
+            }
+        }
+    }
+
+    impl Metaheuristics<i64> for Valley {
+        fn evaluate(&mut self, st: &mut StateWrapper<i64>) -> f64 {
+            valley(*st.state())
+        }
+        fn generate(&mut self) -> StateWrapper<i64> {
+            match self.start.take() {
+                Some(x) => StateWrapper::new(x),
+                None => StateWrapper::new(self.rng.gen_range(0..=100)),
+            }
+        }
+        fn neighbor(&mut self, st: &StateWrapper<i64>) -> StateWrapper<i64> {
+            StateWrapper::new(valley_neighbor(*st.state(), &mut self.rng))
+        }
+    }
+
+    impl IteratedLocalSearch<i64> for Valley {
+        fn kick(&mut self, st: &StateWrapper<i64>) -> StateWrapper<i64> {
+            let x = *st.state();
+            let lo = (x - self.kick).max(0);
+            let hi = (x + self.kick).min(100);
+            StateWrapper::new(self.rng.gen_range(lo..=hi))
+        }
+    }
+
+    #[test]
+    fn test_local_optimum() {
+        let mut problem = Valley::new(Some(0), 0);
+        let initial = problem.generate();
+        assert_eq!(*local_optimum(&mut problem, initial, 30).state(), 30);
+        let initial = StateWrapper::new(60);
+        assert_eq!(*local_optimum(&mut problem, initial, 30).state(), 80);
+    }
+
+    #[test]
+    fn test_acceptance() {
+        let climb = |p: &mut Valley, st| local_optimum(p, st, 30);
+        let mut problem = Valley::new(Some(0), 25);
+        let x = iterated_local_search(&mut problem, 100, IlsAcceptance::Better, climb);
+        assert_eq!(x, 80);
+        let mut problem = Valley::new(Some(0), 25);
+        let x = iterated_local_search(&mut problem, 100, IlsAcceptance::RandomWalk, climb);
+        assert_eq!(x, 80);
+
+        // kick が小さいと x = 30 から抜け出せない
+        let mut problem = Valley::new(Some(0), 5);
+        let x = iterated_local_search(&mut problem, 100, IlsAcceptance::Better, climb);
+        assert_eq!(x, 30);
+        let mut problem = Valley::new(Some(0), 5);
+        let restart = IlsAcceptance::RestartAfter(3);
+        let x = iterated_local_search(&mut problem, 100, restart, |p, st| {
+            hill_climb_from(p, st, 200)
+        });
+        assert_eq!(x, 80);
+    }
+}
@@ -0,0 +1,297 @@
+use crate::heuristics::budget::{Budget, Timer};
+use rand::Rng;
+use std::cmp::Ordering;
+
+/// 手番制のゲームや逐次的な意思決定の問題
+pub trait Game: Clone {
+    type Action: Clone;
+    fn legal_actions(&self) -> Vec<Self::Action>;
+    fn apply(&mut self, action: &Self::Action);
+    fn is_terminal(&self) -> bool;
+    /// player から見た報酬 (0 以上 1 以下にしておくと exploration の目安がそのまま使える)
+    /// プレイアウトを rollout_depth 手で打ち切ったときは終局でない状態でも呼ばれる
+    /// NaN を返してもパニックはしないが、その子の評価は意味をなさなくなる
+    fn reward(&self, player: usize) -> f64;
+    /// 手番のプレイヤー。一人用なら 0 のままでよい
+    fn player(&self) -> usize {
+        0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MctsConfig {
+    /// UCT の探索項の係数
+    pub exploration: f64,
+    /// プレイアウトで進める最大の手数
+    pub rollout_depth: usize,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        Self {
+            exploration: std::f64::consts::SQRT_2,
+            rollout_depth: std::usize::MAX,
+        }
+    }
+}
+
+/// プレイアウトで合法手から一様に選ぶ
+pub fn random_policy<G: Game, R: Rng>(_game: &G, actions: &[G::Action], rng: &mut R) -> usize {
+    rng.gen_range(0..actions.len())
+}
+
+struct Node<A> {
+    parent: usize,
+    children: Vec<usize>,
+    untried: Vec<A>,
+    action: Option<A>,
+    /// この節点に至る手を指したプレイヤー
+    mover: usize,
+    visits: f64,
+    total: f64,
+}
+
+/// root から budget だけ探索し、最も多く訪れた手を返す。合法手がなければ None
+/// policy(game, actions, rng) はプレイアウトで指す手の添字を返す (random_policy など)
+/// 反復回数の予算はプレイアウトの回数として数える
+pub fn mcts<G, B, R, P>(
+    root: &G,
+    budget: B,
+    config: &MctsConfig,
+    rng: &mut R,
+    mut policy: P,
+) -> Option<G::Action>
+where
+    G: Game,
+    B: Into<Budget>,
+    R: Rng,
+    P: FnMut(&G, &[G::Action], &mut R) -> usize,
+{
+    if root.is_terminal() {
+        return None;
+    }
+    let mut nodes = vec![Node {
+        parent: 0,
+        children: vec![],
+        untried: root.legal_actions(),
+        action: None,
+        mover: root.player(),
+        visits: 0.,
+        total: 0.,
+    }];
+    let mut timer = Timer::new(budget);
+
+    while timer.tick() {
+        let mut game = root.clone();
+        let mut v = 0;
+
+        // 選択
+        while nodes[v].untried.is_empty() && !nodes[v].children.is_empty() {
+            let log_n = nodes[v].visits.ln();
+            let c = config.exploration;
+            let uct = |u: &Node<G::Action>| u.total / u.visits + c * (log_n / u.visits).sqrt();
+            let &next = nodes[v]
+                .children
+                .iter()
+                .max_by(|&&a, &&b| {
+                    uct(&nodes[a])
+                        .partial_cmp(&uct(&nodes[b]))
+                        .unwrap_or(Ordering::Equal)
+                })
+                .unwrap();
+            game.apply(nodes[next].action.as_ref().unwrap());
+            v = next;
+        }
+
+        // 展開
+        if !nodes[v].untried.is_empty() {
+            let i = rng.gen_range(0..nodes[v].untried.len());
+            let action = nodes[v].untried.swap_remove(i);
+            let mover = game.player();
+            game.apply(&action);
+            let untried = if game.is_terminal() {
+                vec![]
+            } else {
+                game.legal_actions()
+            };
+            nodes.push(Node {
+                parent: v,
+                children: vec![],
+                untried,
+                action: Some(action),
+                mover,
+                visits: 0.,
+                total: 0.,
+            });
+            let u = nodes.len() - 1;
+            nodes[v].children.push(u);
+            v = u;
+        }
+
+        // プレイアウト
+        let mut depth = 0;
+        while depth < config.rollout_depth && !game.is_terminal() {
+            let actions = game.legal_actions();
+            if actions.is_empty() {
+                break;
+            }
+            let i = policy(&game, &actions, rng);
+            game.apply(&actions[i]);
+            depth += 1;
+        }
+
+        // 逆伝播
+        loop {
+            nodes[v].visits += 1.;
+            nodes[v].total += game.reward(nodes[v].mover);
+            if v == 0 {
+                break;
+            }
+            v = nodes[v].parent;
+        }
+    }
+
+    let children = &nodes[0].children;
+    let best = children.iter().max_by(|&&a, &&b| {
+        nodes[a]
+            .visits
+            .partial_cmp(&nodes[b].visits)
+            .unwrap_or(Ordering::Equal)
+    });
+    match best {
+        Some(&u) => nodes[u].action.clone(),
+        None => root.legal_actions().into_iter().next(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::random::XorShift;
+    use rand::SeedableRng;
+    use std::time::Duration;
+
+    // 石を 1 から 3 個取り合い、最後の石を取った方が勝ち
+    #[derive(Clone)]
+    struct Nim {
+        stones: usize,
+        turn: usize,
+    }
+
+    impl Game for Nim {
+        type Action = usize;
+        fn legal_actions(&self) -> Vec<usize> {
+            (1..=3.min(self.stones)).collect()
+        }
+        fn apply(&mut self, &take: &usize) {
+            self.stones -= take;
+            self.turn ^= 1;
+        }
+        fn is_terminal(&self) -> bool {
+            self.stones == 0
+        }
+        fn reward(&self, player: usize) -> f64 {
+            // 最後に取ったのは手番でない方
+            if self.turn != player {
+                1.
+            } else {
+                0.
+            }
+        }
+        fn player(&self) -> usize {
+            self.turn
+        }
+    }
+
+    // 長さ 8 の 01 列を 1 文字ずつ決め、10 の並びの個数を最大化する
+    #[derive(Clone)]
+    struct Pattern(Vec<u8>);
+
+    impl Game for Pattern {
+        type Action = u8;
+        fn legal_actions(&self) -> Vec<u8> {
+            vec![0, 1]
+        }
+        fn apply(&mut self, &c: &u8) {
+            self.0.push(c);
+        }
+        fn is_terminal(&self) -> bool {
+            self.0.len() == 8
+        }
+        fn reward(&self, _player: usize) -> f64 {
+            self.0.windows(2).filter(|w| w == &[1, 0]).count() as f64 / 4.
+        }
+    }
+
+    // 報酬が常に NaN
+    #[derive(Clone)]
+    struct Broken(u8);
+
+    impl Game for Broken {
+        type Action = u8;
+        fn legal_actions(&self) -> Vec<u8> {
+            vec![0, 1]
+        }
+        fn apply(&mut self, _: &u8) {
+            self.0 += 1;
+        }
+        fn is_terminal(&self) -> bool {
+            self.0 == 3
+        }
+        fn reward(&self, _player: usize) -> f64 {
+            std::f64::NAN
+        }
+    }
+
+    #[test]
+    fn test_nim() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let config = MctsConfig::default();
+        for &(stones, take) in &[(5, 1), (6, 2), (7, 3), (9, 1)] {
+            let nim = Nim { stones, turn: 0 };
+            let action = mcts(&nim, 5000, &config, &mut rng, random_policy);
+            assert_eq!(action, Some(take));
+        }
+        let nim = Nim { stones: 0, turn: 0 };
+        assert_eq!(mcts(&nim, 10, &config, &mut rng, random_policy), None);
+    }
+
+    #[test]
+    fn test_single_player() {
+        let mut rng = XorShift::seed_from_u64(2);
+        let config = MctsConfig::default();
+        let mut game = Pattern(vec![]);
+        while !game.is_terminal() {
+            let action = mcts(&game, 2000, &config, &mut rng, random_policy).unwrap();
+            game.apply(&action);
+        }
+        assert_eq!(game.0, vec![1, 0, 1, 0, 1, 0, 1, 0]);
+
+        // 常に 1 を選ぶプレイアウトと打ち切りでも最後まで進められる
+        let config = MctsConfig {
+            exploration: 0.5,
+            rollout_depth: 2,
+        };
+        let mut game = Pattern(vec![]);
+        while !game.is_terminal() {
+            let budget = Duration::from_millis(10);
+            let action = mcts(
+                &game,
+                budget,
+                &config,
+                &mut rng,
+                |_: &Pattern, _: &[u8], _: &mut XorShift| 1,
+            );
+            game.apply(&action.unwrap());
+        }
+        assert_eq!(game.0.len(), 8);
+    }
+
+    #[test]
+    fn test_nan_reward() {
+        let mut rng = XorShift::seed_from_u64(3);
+        let config = MctsConfig::default();
+        let action = mcts(&Broken(0), 200, &config, &mut rng, random_policy);
+        assert!(action.is_some());
+    }
+}
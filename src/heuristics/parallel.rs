@@ -0,0 +1,147 @@
+use crate::heuristics::{budget::Budget, Metaheuristics, State, StateWrapper};
+use crate::utils::random::XorShift;
+use rand::SeedableRng;
+use std::sync::Arc;
+use std::thread;
+
+/// threads 本の探索をそれぞれ別のスレッドで走らせ、全体で最良の状態を返す
+/// 予算を rounds 回に分け、区切りごとに全スレッドを全体の最良解から再開させる
+///
+/// - new_problem(i): i 番目のスレッドで使う問題を作る
+/// - chain(problem, initial, budget, rng): initial から budget だけ探索し、最良の状態を返す
+///   (anneal_from や hill_climb_from など)
+///
+/// i 番目のスレッドの乱数は XorShift::seed_from_u64(seed + i) で作るので、
+/// 反復回数の予算を使えば結果は再現できる
+pub fn parallel<S, T, G, F, B>(
+    new_problem: G,
+    budget: B,
+    threads: usize,
+    rounds: usize,
+    seed: u64,
+    chain: F,
+) -> S
+where
+    S: State + Send + 'static,
+    T: Metaheuristics<S> + Send + 'static,
+    G: Fn(usize) -> T,
+    F: Fn(&mut T, StateWrapper<S>, Budget, &mut XorShift) -> StateWrapper<S>
+        + Send
+        + Sync
+        + 'static,
+    B: Into<Budget>,
+{
+    assert!(threads > 0, "threads must be positive");
+    assert!(rounds > 0, "rounds must be positive");
+    let budget = budget.into().split(rounds);
+    let chain = Arc::new(chain);
+    let mut workers = (0..threads)
+        .map(|i| {
+            let mut problem = new_problem(i);
+            let initial = problem.generate();
+            let rng = XorShift::seed_from_u64(seed.wrapping_add(i as u64));
+            (problem, rng, initial)
+        })
+        .collect::<Vec<_>>();
+    let mut best: Option<(f64, StateWrapper<S>)> = None;
+
+    for _ in 0..rounds {
+        let handles = workers
+            .into_iter()
+            .map(|(mut problem, mut rng, initial)| {
+                let chain = Arc::clone(&chain);
+                thread::spawn(move || {
+                    let mut result = chain(&mut problem, initial, budget, &mut rng);
+                    let score = problem.score(&mut result);
+                    (problem, rng, score, result)
+                })
+            })
+            .collect::<Vec<_>>();
+        let results = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+
+        for (_, _, score, result) in results.iter() {
+            let improved = match &best {
+                Some(b) => *score > b.0,
+                None => true,
+            };
+            if improved {
+                best = Some((*score, result.clone()));
+            }
+        }
+        let global = &best.as_ref().unwrap().1;
+        workers = results
+            .into_iter()
+            .map(|(problem, rng, _, _)| (problem, rng, global.clone()))
+            .collect();
+    }
+    best.unwrap().1.state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::acceptance::Metropolis;
+    use crate::heuristics::annealing::anneal_from;
+    use crate::heuristics::hill_climb::hill_climb_from;
+    use crate::heuristics::schedule::TimeProportional;
+    use rand::Rng;
+    use std::time::Duration;
+
+    // (x - 20)^2 (x + 20)^2 の符号を反転したものを最大化する。極大は x = -20, 20
+    // x = 20 の方にだけボーナスがある
+    struct TwoPeaks(XorShift);
+
+    impl Metaheuristics<i64> for TwoPeaks {
+        fn evaluate(&mut self, st: &mut StateWrapper<i64>) -> f64 {
+            let x = *st.state();
+            let bonus = if x == 20 { 1. } else { 0. };
+            -((x - 20) * (x - 20) * (x + 20) * (x + 20)) as f64 + bonus
+        }
+        fn generate(&mut self) -> StateWrapper<i64> {
+            StateWrapper::new(self.0.gen_range(-100..100))
+        }
+        fn neighbor(&mut self, st: &StateWrapper<i64>) -> StateWrapper<i64> {
+            StateWrapper::new(st.state() + if self.0.gen_bool(0.5) { 1 } else { -1 })
+        }
+    }
+
+    fn new_problem(i: usize) -> TwoPeaks {
+        TwoPeaks(XorShift::seed_from_u64(100 + i as u64))
+    }
+
+    #[test]
+    fn test_parallel_hill_climb() {
+        let chain = |p: &mut TwoPeaks, initial, budget, _rng: &mut XorShift| {
+            hill_climb_from(p, initial, budget)
+        };
+        let x = parallel(new_problem, 4000, 8, 2, 1, chain);
+        assert_eq!(x, 20);
+    }
+
+    #[test]
+    fn test_parallel_anneal() {
+        let run = |seed| {
+            parallel(new_problem, 10000, 4, 5, seed, |p, initial, budget, rng| {
+                let schedule = TimeProportional {
+                    start: 1e5,
+                    end: 1.,
+                };
+                anneal_from(p, initial, budget, schedule, Metropolis, rng)
+            })
+        };
+        assert_eq!(run(1), 20);
+        assert_eq!(run(2), run(2));
+        let x = parallel(
+            new_problem,
+            Duration::from_millis(50),
+            4,
+            2,
+            3,
+            |p, initial, budget, rng| anneal_from(p, initial, budget, |_| 1e3, Metropolis, rng),
+        );
+        assert!(x == 20 || x == -20);
+    }
+}
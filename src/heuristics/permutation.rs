@@ -0,0 +1,476 @@
+//! 順列や列を状態とする問題のための近傍
+//! PermutationMove を MoveMetaheuristics::Move に使い、apply と rollback は
+//! mv.apply(st.state_mut()) と mv.inverse().apply(st.state_mut()) で書ける
+
+use rand::Rng;
+
+/// 長さ n の列に対する遷移。区間は半開区間
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PermutationMove {
+    /// i 番目と j 番目を入れ替える
+    Swap(usize, usize),
+    /// i 番目の要素を取り除き、j 番目に来るように挿入する
+    Insert(usize, usize),
+    /// [i, j) を反転する (2-opt)
+    Reverse(usize, usize),
+    /// [start, start + len) を取り除き、to 番目から始まるように挿入する (Or-opt)
+    /// reversed なら向きを反転して挿入する
+    OrOpt {
+        start: usize,
+        len: usize,
+        to: usize,
+        reversed: bool,
+    },
+    /// [a, b) と [c, d) を入れ替える。a < b <= c < d
+    BlockExchange(usize, usize, usize, usize),
+}
+
+/// PermutationMove::random で生成する遷移の種類
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MoveKind {
+    Swap,
+    Insert,
+    Reverse,
+    /// 動かす区間の長さの上限
+    OrOpt(usize),
+    BlockExchange,
+}
+
+/// 遷移後の列を、遷移前の列の区間 [l, r) (反転するかどうか) の並びで表したもの
+struct Segments {
+    len: usize,
+    data: [(usize, usize, bool); 5],
+}
+
+impl Segments {
+    fn new(n: usize, cuts: &[(usize, usize, bool)]) -> Self {
+        let mut segments = Self {
+            len: 0,
+            data: [(0, 0, false); 5],
+        };
+        for &(l, r, rev) in cuts {
+            if l < r {
+                segments.data[segments.len] = (l, r, rev);
+                segments.len += 1;
+            }
+        }
+        debug_assert_eq!(segments.iter().map(|s| s.1 - s.0).sum::<usize>(), n);
+        segments
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &(usize, usize, bool)> {
+        self.data[..self.len].iter()
+    }
+}
+
+impl PermutationMove {
+    /// 長さ n の列に対する kind の遷移を一様に近い分布で選ぶ
+    pub fn random<R: Rng>(rng: &mut R, n: usize, kind: MoveKind) -> Self {
+        assert!(n >= 2, "sequence must have at least 2 elements");
+        let distinct = |rng: &mut R| {
+            let i = rng.gen_range(0..n);
+            let j = rng.gen_range(0..n - 1);
+            (i, if j >= i { j + 1 } else { j })
+        };
+        match kind {
+            MoveKind::Swap => {
+                let (i, j) = distinct(rng);
+                PermutationMove::Swap(i, j)
+            }
+            MoveKind::Insert => {
+                let (i, j) = distinct(rng);
+                PermutationMove::Insert(i, j)
+            }
+            MoveKind::Reverse => {
+                let (i, j) = distinct(rng);
+                let (i, j) = if i < j { (i, j) } else { (j, i) };
+                PermutationMove::Reverse(i, j + 1)
+            }
+            MoveKind::OrOpt(max_len) => {
+                let len = rng.gen_range(1..=max_len.max(1).min(n - 1));
+                let start = rng.gen_range(0..=n - len);
+                let mut to = rng.gen_range(0..n - len);
+                if to >= start {
+                    to += 1;
+                }
+                PermutationMove::OrOpt {
+                    start,
+                    len,
+                    to,
+                    reversed: rng.gen(),
+                }
+            }
+            MoveKind::BlockExchange => loop {
+                let mut p = [0; 4];
+                for x in p.iter_mut() {
+                    *x = rng.gen_range(0..=n);
+                }
+                p.sort_unstable();
+                if p[0] < p[1] && p[2] < p[3] {
+                    break PermutationMove::BlockExchange(p[0], p[1], p[2], p[3]);
+                }
+            },
+        }
+    }
+
+    /// p を遷移後の列に書き換える。O(動く範囲の長さ)
+    pub fn apply<T>(&self, p: &mut [T]) {
+        match *self {
+            PermutationMove::Swap(i, j) => p.swap(i, j),
+            PermutationMove::Insert(i, j) => {
+                if i < j {
+                    p[i..=j].rotate_left(1);
+                } else {
+                    p[j..=i].rotate_right(1);
+                }
+            }
+            PermutationMove::Reverse(i, j) => p[i..j].reverse(),
+            PermutationMove::OrOpt {
+                start,
+                len,
+                to,
+                reversed,
+            } => {
+                if reversed {
+                    p[start..start + len].reverse();
+                }
+                if to < start {
+                    p[to..start + len].rotate_right(len);
+                } else {
+                    p[start..to + len].rotate_left(len);
+                }
+            }
+            PermutationMove::BlockExchange(a, b, c, d) => {
+                p[a..d].reverse();
+                p[a..a + d - c].reverse();
+                p[a + d - c..d - (b - a)].reverse();
+                p[d - (b - a)..d].reverse();
+            }
+        }
+    }
+
+    /// 遷移後の列に適用すると遷移前の列に戻る遷移
+    pub fn inverse(&self) -> Self {
+        match *self {
+            PermutationMove::Insert(i, j) => PermutationMove::Insert(j, i),
+            PermutationMove::OrOpt {
+                start,
+                len,
+                to,
+                reversed,
+            } => PermutationMove::OrOpt {
+                start: to,
+                len,
+                to: start,
+                reversed,
+            },
+            PermutationMove::BlockExchange(a, b, c, d) => {
+                PermutationMove::BlockExchange(a, a + d - c, d - (b - a), d)
+            }
+            mv => mv,
+        }
+    }
+
+    fn segments(&self, n: usize) -> Segments {
+        match *self {
+            // 同じ位置どうしの入れ替えは何もしない
+            PermutationMove::Swap(i, j) if i == j => Segments::new(n, &[(0, n, false)]),
+            PermutationMove::Swap(i, j) => {
+                let (a, b) = if i < j { (i, j) } else { (j, i) };
+                let cuts = [
+                    (0, a, false),
+                    (b, b + 1, false),
+                    (a + 1, b, false),
+                    (a, a + 1, false),
+                    (b + 1, n, false),
+                ];
+                Segments::new(n, &cuts)
+            }
+            PermutationMove::Insert(i, j) => {
+                let move_to = PermutationMove::OrOpt {
+                    start: i,
+                    len: 1,
+                    to: j,
+                    reversed: false,
+                };
+                move_to.segments(n)
+            }
+            PermutationMove::Reverse(i, j) => {
+                Segments::new(n, &[(0, i, false), (i, j, true), (j, n, false)])
+            }
+            PermutationMove::OrOpt {
+                start,
+                len,
+                to,
+                reversed,
+            } => {
+                let cuts = if to < start {
+                    [
+                        (0, to, false),
+                        (start, start + len, reversed),
+                        (to, start, false),
+                        (start + len, n, false),
+                    ]
+                } else {
+                    [
+                        (0, start, false),
+                        (start + len, to + len, false),
+                        (start, start + len, reversed),
+                        (to + len, n, false),
+                    ]
+                };
+                Segments::new(n, &cuts)
+            }
+            PermutationMove::BlockExchange(a, b, c, d) => {
+                let cuts = [
+                    (0, a, false),
+                    (c, d, false),
+                    (b, c, false),
+                    (a, b, false),
+                    (d, n, false),
+                ];
+                Segments::new(n, &cuts)
+            }
+        }
+    }
+
+    /// 遷移による path_cost(p, cyclic, dist) の変化量を O(1) 回の dist の呼び出しで求める
+    /// 区間を反転する遷移では dist が対称であること
+    pub fn delta<F: Fn(usize, usize) -> f64>(&self, p: &[usize], cyclic: bool, dist: F) -> f64 {
+        let n = p.len();
+        let segments = self.segments(n);
+        let ends = |&(l, r, rev): &(usize, usize, bool)| {
+            if rev {
+                (p[r - 1], p[l])
+            } else {
+                (p[l], p[r - 1])
+            }
+        };
+        let mut removed = 0.;
+        let mut added = 0.;
+        let mut last: Option<usize> = None;
+        for segment in segments.iter() {
+            let r = segment.1;
+            if r < n {
+                removed += dist(p[r - 1], p[r]);
+            }
+            let (head, tail) = ends(segment);
+            if let Some(last) = last {
+                added += dist(last, head);
+            }
+            last = Some(tail);
+        }
+        if let (true, Some(last)) = (cyclic, last) {
+            removed += dist(p[n - 1], p[0]);
+            added += dist(last, ends(&segments.data[0]).0);
+        }
+        added - removed
+    }
+}
+
+/// 隣り合う要素の間の dist の総和。cyclic なら末尾から先頭への分も足す
+pub fn path_cost<F: Fn(usize, usize) -> f64>(p: &[usize], cyclic: bool, dist: F) -> f64 {
+    let mut cost = p.windows(2).map(|w| dist(w[0], w[1])).sum::<f64>();
+    if cyclic && !p.is_empty() {
+        cost += dist(p[p.len() - 1], p[0]);
+    }
+    cost
+}
+
+/// 0..n の一様ランダムな順列
+pub fn random_permutation<R: Rng>(rng: &mut R, n: usize) -> Vec<usize> {
+    let mut p = (0..n).collect::<Vec<_>>();
+    for i in (1..n).rev() {
+        p.swap(i, rng.gen_range(0..=i));
+    }
+    p
+}
+
+/// first から始まり、残りが一様ランダムな 0..n の順列
+pub fn random_permutation_from<R: Rng>(rng: &mut R, n: usize, first: usize) -> Vec<usize> {
+    let mut p = random_permutation(rng, n);
+    let i = p.iter().position(|&x| x == first).unwrap();
+    p.swap(0, i);
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::acceptance::Metropolis;
+    use crate::heuristics::annealing::anneal_delta;
+    use crate::heuristics::hill_climb::hill_climb_delta;
+    use crate::heuristics::schedule::TimeProportional;
+    use crate::heuristics::{Metaheuristics, MoveMetaheuristics, StateWrapper};
+    use crate::utils::random::XorShift;
+    use rand::SeedableRng;
+
+    const KINDS: [MoveKind; 5] = [
+        MoveKind::Swap,
+        MoveKind::Insert,
+        MoveKind::Reverse,
+        MoveKind::OrOpt(3),
+        MoveKind::BlockExchange,
+    ];
+
+    #[test]
+    fn test_apply() {
+        let mut p = (0..6).collect::<Vec<_>>();
+        PermutationMove::Swap(4, 1).apply(&mut p);
+        assert_eq!(p, vec![0, 4, 2, 3, 1, 5]);
+        PermutationMove::Insert(1, 4).apply(&mut p);
+        assert_eq!(p, vec![0, 2, 3, 1, 4, 5]);
+        PermutationMove::Reverse(2, 5).apply(&mut p);
+        assert_eq!(p, vec![0, 2, 4, 1, 3, 5]);
+        let mv = PermutationMove::OrOpt {
+            start: 3,
+            len: 2,
+            to: 0,
+            reversed: true,
+        };
+        mv.apply(&mut p);
+        assert_eq!(p, vec![3, 1, 0, 2, 4, 5]);
+        PermutationMove::BlockExchange(0, 1, 3, 5).apply(&mut p);
+        assert_eq!(p, vec![2, 4, 1, 0, 3, 5]);
+    }
+
+    #[test]
+    fn test_inverse_and_delta() {
+        let mut rng = XorShift::seed_from_u64(1);
+        for n in 2..10 {
+            let dist =
+                |a: usize, b: usize| ((a * 7 + b * 7) % 13) as f64 + (a as f64 - b as f64).abs();
+            for _ in 0..200 {
+                let p = random_permutation(&mut rng, n);
+                for &kind in KINDS.iter() {
+                    let mv = PermutationMove::random(&mut rng, n, kind);
+                    let mut q = p.clone();
+                    mv.apply(&mut q);
+                    for &cyclic in &[false, true] {
+                        let expected = path_cost(&q, cyclic, dist) - path_cost(&p, cyclic, dist);
+                        let delta = mv.delta(&p, cyclic, dist);
+                        assert!((delta - expected).abs() < 1e-9, "{:?} {:?}", p, mv);
+                    }
+                    mv.inverse().apply(&mut q);
+                    assert_eq!(p, q);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_noop_swap() {
+        let p = vec![3, 0, 4, 1, 2];
+        let dist = |a: usize, b: usize| (a * 5 + b * 3) as f64;
+        for i in 0..p.len() {
+            let mv = PermutationMove::Swap(i, i);
+            for &cyclic in &[false, true] {
+                assert!(mv.delta(&p, cyclic, dist).abs() < 1e-9);
+            }
+            let mut q = p.clone();
+            mv.apply(&mut q);
+            assert_eq!(p, q);
+        }
+    }
+
+    #[test]
+    fn test_random_permutation() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let mut count = [[0; 3]; 3];
+        for _ in 0..3000 {
+            let p = random_permutation(&mut rng, 3);
+            for (i, &x) in p.iter().enumerate() {
+                count[i][x] += 1;
+            }
+        }
+        assert!(count.iter().flatten().all(|&c| 800 < c && c < 1200));
+        let p = random_permutation_from(&mut rng, 10, 7);
+        assert_eq!(p[0], 7);
+        let mut sorted = p;
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+    }
+
+    // 円周上に等間隔に並んだ点を巡回する最短路 (順に並べたものが最適)
+    struct Tsp {
+        dist: Vec<Vec<f64>>,
+        rng: XorShift,
+    }
+
+    impl Tsp {
+        fn new(n: usize) -> Self {
+            let point = |i: usize| {
+                let t = 2. * std::f64::consts::PI * i as f64 / n as f64;
+                (t.cos(), t.sin())
+            };
+            let dist = (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| {
+                            let (p, q) = (point(i), point(j));
+                            (p.0 - q.0).hypot(p.1 - q.1)
+                        })
+                        .collect()
+                })
+                .collect();
+            Self {
+                dist,
+                rng: XorShift::seed_from_u64(1),
+            }
+        }
+    }
+
+    impl Metaheuristics<Vec<usize>> for Tsp {
+        fn evaluate(&mut self, st: &mut StateWrapper<Vec<usize>>) -> f64 {
+            -path_cost(st.state(), true, |a, b| self.dist[a][b])
+        }
+        fn generate(&mut self) -> StateWrapper<Vec<usize>> {
+            StateWrapper::new(random_permutation_from(&mut self.rng, self.dist.len(), 0))
+        }
+        fn neighbor(&mut self, st: &StateWrapper<Vec<usize>>) -> StateWrapper<Vec<usize>> {
+            let mv = self.propose(st);
+            let mut next = st.clone();
+            self.apply(&mut next, &mv);
+            next
+        }
+    }
+
+    impl MoveMetaheuristics<Vec<usize>> for Tsp {
+        type Move = PermutationMove;
+        fn propose(&mut self, st: &StateWrapper<Vec<usize>>) -> PermutationMove {
+            let n = st.state().len();
+            let kind = KINDS[self.rng.gen_range(0..KINDS.len())];
+            PermutationMove::random(&mut self.rng, n, kind)
+        }
+        fn apply(&mut self, st: &mut StateWrapper<Vec<usize>>, mv: &PermutationMove) {
+            mv.apply(st.state_mut());
+        }
+        fn rollback(&mut self, st: &mut StateWrapper<Vec<usize>>, mv: &PermutationMove) {
+            mv.inverse().apply(st.state_mut());
+        }
+        fn delta(&mut self, st: &mut StateWrapper<Vec<usize>>, mv: &PermutationMove) -> f64 {
+            let dist = &self.dist;
+            -mv.delta(st.state(), true, |a, b| dist[a][b])
+        }
+    }
+
+    #[test]
+    fn test_tsp() {
+        let n = 20;
+        let optimal = 2. * n as f64 * (std::f64::consts::PI / n as f64).sin();
+        let mut problem = Tsp::new(n);
+        let tour = hill_climb_delta(&mut problem, 20000);
+        let cost = path_cost(&tour, true, |a, b| problem.dist[a][b]);
+        assert!(cost < optimal * 1.2);
+
+        let mut problem = Tsp::new(n);
+        let mut rng = XorShift::seed_from_u64(2);
+        let schedule = TimeProportional {
+            start: 0.5,
+            end: 1e-3,
+        };
+        let tour = anneal_delta(&mut problem, 50000, schedule, Metropolis, &mut rng);
+        let cost = path_cost(&tour, true, |a, b| problem.dist[a][b]);
+        assert!((cost - optimal).abs() < 1e-9);
+    }
+}
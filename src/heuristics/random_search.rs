@@ -1,16 +1,25 @@
-use crate::heuristics::{Metaheuristics, State};
-use std::time::{Duration, Instant};
+use crate::heuristics::{
+    budget::{Budget, Timer},
+    telemetry::Record,
+    Metaheuristics, State,
+};
 
-pub fn random_search<S: State, T: Metaheuristics<S>>(problem: &mut T, runtime: Duration) -> S {
+pub fn random_search<S: State, T: Metaheuristics<S>, B: Into<Budget>>(
+    problem: &mut T,
+    budget: B,
+) -> S {
     let mut best_candidate = problem.generate();
-    let start_time = Instant::now();
+    let mut timer = Timer::new(budget);
 
-    while Instant::now().duration_since(start_time) < runtime {
+    while timer.tick() {
         let mut next_candidate = problem.generate();
+        let next_score = problem.score(&mut next_candidate);
 
-        if problem.evaluate(&mut next_candidate) > problem.evaluate(&mut best_candidate) {
+        if next_score > problem.score(&mut best_candidate) {
             best_candidate = next_candidate;
         }
+        let best_score = problem.score(&mut best_candidate);
+        problem.observe(&Record::new(&timer, next_score, best_score, None, None));
     }
     best_candidate.state
 }
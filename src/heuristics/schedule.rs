@@ -0,0 +1,176 @@
+pub trait Schedule {
+    /// progress は探索の進み具合 (0 以上 1 未満)
+    fn temperature(&mut self, progress: f64) -> f64;
+    /// 探索の最初の状態に戻す
+    fn reset(&mut self) {}
+}
+
+impl<F: FnMut(f64) -> f64> Schedule for F {
+    fn temperature(&mut self, progress: f64) -> f64 {
+        self(progress)
+    }
+}
+
+/// 呼ばれるたびに温度を ratio 倍する
+#[derive(Clone, Debug)]
+pub struct Geometric {
+    initial: f64,
+    current: f64,
+    ratio: f64,
+}
+
+impl Geometric {
+    pub fn new(initial: f64, ratio: f64) -> Self {
+        Self {
+            initial,
+            current: initial,
+            ratio,
+        }
+    }
+}
+
+impl Schedule for Geometric {
+    fn temperature(&mut self, _progress: f64) -> f64 {
+        let t = self.current;
+        self.current *= self.ratio;
+        t
+    }
+    fn reset(&mut self) {
+        self.current = self.initial;
+    }
+}
+
+/// start から end まで進み具合に比例して変化する
+#[derive(Clone, Debug)]
+pub struct Linear {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl Schedule for Linear {
+    fn temperature(&mut self, progress: f64) -> f64 {
+        self.start + (self.end - self.start) * progress
+    }
+}
+
+/// 温度の対数が進み具合に比例して start から end まで変化する
+#[derive(Clone, Debug)]
+pub struct TimeProportional {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl Schedule for TimeProportional {
+    fn temperature(&mut self, progress: f64) -> f64 {
+        self.start.powf(1. - progress) * self.end.powf(progress)
+    }
+}
+
+/// exp(-rate * progress^power) で 1 から減衰する
+/// FixedProbability と組み合わせると、悪化を受理する確率として使える
+#[derive(Clone, Debug)]
+pub struct ExponentialDecay {
+    pub rate: f64,
+    pub power: f64,
+}
+
+impl Schedule for ExponentialDecay {
+    fn temperature(&mut self, progress: f64) -> f64 {
+        (-self.rate * progress.powf(self.power)).exp()
+    }
+}
+
+/// 探索を cycles 等分し、区間ごとに inner を最初からやり直す
+#[derive(Clone, Debug)]
+pub struct Reheating<Sc: Schedule> {
+    inner: Sc,
+    cycles: usize,
+    cycle: usize,
+}
+
+impl<Sc: Schedule> Reheating<Sc> {
+    pub fn new(inner: Sc, cycles: usize) -> Self {
+        assert!(cycles > 0, "cycles must be positive");
+        Self {
+            inner,
+            cycles,
+            cycle: 0,
+        }
+    }
+}
+
+impl<Sc: Schedule> Schedule for Reheating<Sc> {
+    fn temperature(&mut self, progress: f64) -> f64 {
+        let x = progress * self.cycles as f64;
+        let cycle = (x as usize).min(self.cycles - 1);
+        if cycle != self.cycle {
+            self.cycle = cycle;
+            self.inner.reset();
+        }
+        self.inner.temperature(x - cycle as f64)
+    }
+    fn reset(&mut self) {
+        self.cycle = 0;
+        self.inner.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_geometric() {
+        let mut sc = Geometric::new(8., 0.5);
+        assert!(approx(sc.temperature(0.), 8.));
+        assert!(approx(sc.temperature(0.), 4.));
+        assert!(approx(sc.temperature(0.), 2.));
+        sc.reset();
+        assert!(approx(sc.temperature(0.), 8.));
+    }
+
+    #[test]
+    fn test_linear_and_time_proportional() {
+        let mut sc = Linear {
+            start: 10.,
+            end: 2.,
+        };
+        assert!(approx(sc.temperature(0.), 10.));
+        assert!(approx(sc.temperature(0.5), 6.));
+        let mut sc = TimeProportional {
+            start: 100.,
+            end: 1.,
+        };
+        assert!(approx(sc.temperature(0.), 100.));
+        assert!(approx(sc.temperature(0.5), 10.));
+        let mut sc = ExponentialDecay {
+            rate: 10.,
+            power: 3.,
+        };
+        assert!(approx(sc.temperature(0.), 1.));
+        assert!(approx(sc.temperature(0.5), (-1.25f64).exp()));
+        let mut sc = |p: f64| 1. - p;
+        assert!(approx(sc.temperature(0.25), 0.75));
+    }
+
+    #[test]
+    fn test_reheating() {
+        let mut sc = Reheating::new(Geometric::new(8., 0.5), 2);
+        assert!(approx(sc.temperature(0.1), 8.));
+        assert!(approx(sc.temperature(0.2), 4.));
+        assert!(approx(sc.temperature(0.6), 8.));
+        let mut sc = Reheating::new(
+            Linear {
+                start: 10.,
+                end: 0.,
+            },
+            4,
+        );
+        assert!(approx(sc.temperature(0.125), 5.));
+        assert!(approx(sc.temperature(0.625), 5.));
+    }
+}
@@ -0,0 +1,225 @@
+use crate::heuristics::{
+    budget::{Budget, Timer},
+    telemetry::Record,
+    Metaheuristics, State, StateWrapper,
+};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub trait TabuSearch<S: State>: Metaheuristics<S> {
+    /// 遷移を選ぶと、その属性は tenure 回の反復の間禁止される
+    type Attribute: Hash + Eq + Clone;
+    /// st から遷移できる (属性, 遷移先) を列挙する
+    fn moves(&mut self, st: &StateWrapper<S>) -> Vec<(Self::Attribute, StateWrapper<S>)>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Aspiration {
+    /// 禁止された遷移は選ばない
+    Never,
+    /// 禁止された遷移でも、これまでの最良解を更新するなら選ぶ
+    BestEver,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Restart {
+    Never,
+    /// k 回続けて最良解が更新されなければ、最良解から探索し直す
+    Intensify(usize),
+    /// k 回続けて最良解が更新されなければ、generate() から探索し直す
+    Diversify(usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct TabuConfig {
+    pub tenure: usize,
+    pub aspiration: Aspiration,
+    pub restart: Restart,
+}
+
+impl Default for TabuConfig {
+    fn default() -> Self {
+        Self {
+            tenure: 7,
+            aspiration: Aspiration::BestEver,
+            restart: Restart::Never,
+        }
+    }
+}
+
+/// 毎回、禁止されていない遷移の中で最良のものへ (悪化しても) 移る
+/// すべて禁止されている場合は禁止を無視して最良のものへ移る
+pub fn tabu_search<S: State, T: TabuSearch<S>, B: Into<Budget>>(
+    problem: &mut T,
+    budget: B,
+    config: &TabuConfig,
+) -> S {
+    let mut current_candidate = problem.generate();
+    let mut best_score = problem.score(&mut current_candidate);
+    let mut best_candidate = current_candidate.clone();
+    let mut tabu: HashMap<T::Attribute, usize> = HashMap::new();
+    let mut stall = 0;
+    let mut timer = Timer::new(budget);
+
+    while timer.tick() {
+        let iteration = timer.iteration();
+        let mut chosen: Option<(bool, f64, T::Attribute, StateWrapper<S>)> = None;
+        for (attribute, mut next_candidate) in problem.moves(&current_candidate) {
+            let score = problem.score(&mut next_candidate);
+            let is_tabu = match tabu.get(&attribute) {
+                Some(&expire) => expire > iteration,
+                None => false,
+            };
+            let admissible =
+                !is_tabu || (config.aspiration == Aspiration::BestEver && score > best_score);
+            let better = match &chosen {
+                Some((a, s, _, _)) => (admissible, score) > (*a, *s),
+                None => true,
+            };
+            if better {
+                chosen = Some((admissible, score, attribute, next_candidate));
+            }
+        }
+        let (_, score, attribute, next_candidate) = match chosen {
+            Some(x) => x,
+            None => break,
+        };
+
+        current_candidate = next_candidate;
+        tabu.insert(attribute, iteration + config.tenure);
+        if tabu.len() > 4 * config.tenure + 16 {
+            tabu.retain(|_, e| *e > iteration);
+        }
+
+        let improved = score > best_score;
+        if improved {
+            best_score = score;
+            best_candidate = current_candidate.clone();
+        }
+        problem.observe(&Record::new(&timer, score, best_score, None, None));
+        if improved {
+            stall = 0;
+            continue;
+        }
+        stall += 1;
+        match config.restart {
+            Restart::Intensify(k) if stall >= k => {
+                current_candidate = best_candidate.clone();
+            }
+            Restart::Diversify(k) if stall >= k => {
+                current_candidate = problem.generate();
+                let score = problem.score(&mut current_candidate);
+                if score > best_score {
+                    best_score = score;
+                    best_candidate = current_candidate.clone();
+                }
+            }
+            _ => continue,
+        }
+        tabu.clear();
+        stall = 0;
+    }
+    best_candidate.state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::test_problems::{valley, valley_neighbor};
+    use crate::utils::random::XorShift;
+    use rand::{Rng, SeedableRng};
+
+    // test_problems::valley の上を 1 ずつ動く
+    // 遷移の属性は by_direction なら移動の向き、そうでなければ遷移先
+    struct Valley {
+        rng: XorShift,
+        start: Option<i64>,
+        by_direction: bool,
+    }
+
+    impl Valley {
+        fn new(start: Option<i64>) -> Self {
+            Self {
+                rng: XorShift::seed_from_u64(1),
+                start,
+                by_direction: false,
+            }
+        }
+    }
+
+    impl Metaheuristics<i64> for Valley {
+        fn evaluate(&mut self, st: &mut StateWrapper<i64>) -> f64 {
+            valley(*st.state())
+        }
+        fn generate(&mut self) -> StateWrapper<i64> {
+            match self.start {
+                Some(x) => StateWrapper::new(x),
+                None => StateWrapper::new(self.rng.gen_range(0..=100)),
+            }
+        }
+        fn neighbor(&mut self, st: &StateWrapper<i64>) -> StateWrapper<i64> {
+            StateWrapper::new(valley_neighbor(*st.state(), &mut self.rng))
+        }
+    }
+
+    impl TabuSearch<i64> for Valley {
+        type Attribute = i64;
+        fn moves(&mut self, st: &StateWrapper<i64>) -> Vec<(i64, StateWrapper<i64>)> {
+            let x = *st.state();
+            [-1, 1]
+                .iter()
+                .filter(|&&d| 0 <= x + d && x + d <= 100)
+                .map(|&d| {
+                    let attribute = if self.by_direction { d } else { x + d };
+                    (attribute, StateWrapper::new(x + d))
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_tenure() {
+        let config = TabuConfig {
+            tenure: 0,
+            ..TabuConfig::default()
+        };
+        assert_eq!(tabu_search(&mut Valley::new(Some(0)), 1000, &config), 30);
+        let config = TabuConfig {
+            tenure: 40,
+            ..TabuConfig::default()
+        };
+        assert_eq!(tabu_search(&mut Valley::new(Some(0)), 1000, &config), 80);
+    }
+
+    #[test]
+    fn test_aspiration() {
+        // 同じ向きに続けて動けないので、Never では 0 と 1 を往復する
+        // BestEver では最良解を更新する限り同じ向きに動けるので 30 まで登る
+        let mut config = TabuConfig {
+            tenure: 2,
+            aspiration: Aspiration::Never,
+            restart: Restart::Never,
+        };
+        let mut problem = Valley::new(Some(0));
+        problem.by_direction = true;
+        assert_eq!(tabu_search(&mut problem, 100, &config), 1);
+        config.aspiration = Aspiration::BestEver;
+        assert_eq!(tabu_search(&mut problem, 100, &config), 30);
+    }
+
+    #[test]
+    fn test_restart() {
+        let config = TabuConfig {
+            tenure: 40,
+            aspiration: Aspiration::BestEver,
+            restart: Restart::Intensify(5),
+        };
+        assert_eq!(tabu_search(&mut Valley::new(Some(0)), 1000, &config), 30);
+        let config = TabuConfig {
+            tenure: 0,
+            aspiration: Aspiration::BestEver,
+            restart: Restart::Diversify(5),
+        };
+        assert_eq!(tabu_search(&mut Valley::new(None), 1000, &config), 80);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::heuristics::budget::Timer;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// 探索の各反復の時点での様子
+/// Metaheuristics::observe に渡される
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub iteration: usize,
+    pub elapsed: Duration,
+    pub current: f64,
+    pub best: f64,
+    /// 温度を使わない探索では None
+    pub temperature: Option<f64>,
+    /// これまでに遷移を受理した割合。受理の判定がない探索では None
+    pub acceptance_ratio: Option<f64>,
+}
+
+impl Record {
+    pub(crate) fn new(
+        timer: &Timer,
+        current: f64,
+        best: f64,
+        temperature: Option<f64>,
+        accepted: Option<usize>,
+    ) -> Self {
+        let iteration = timer.iteration();
+        Self {
+            iteration,
+            elapsed: timer.elapsed(),
+            current,
+            best,
+            temperature,
+            acceptance_ratio: accepted.map(|a| a as f64 / iteration.max(1) as f64),
+        }
+    }
+}
+
+/// record が interval 回呼ばれるごとに 1 回 CSV で書き出す
+/// 値がない列は空欄にする
+pub struct CsvRecorder<W: Write> {
+    writer: W,
+    interval: usize,
+    skipped: usize,
+    header: bool,
+}
+
+impl<W: Write> CsvRecorder<W> {
+    pub fn new(writer: W, interval: usize) -> Self {
+        assert!(interval > 0, "interval must be positive");
+        Self {
+            writer,
+            interval,
+            skipped: 0,
+            header: false,
+        }
+    }
+
+    pub fn record(&mut self, record: &Record) -> io::Result<()> {
+        if !self.header {
+            self.header = true;
+            writeln!(
+                self.writer,
+                "iteration,elapsed,current,best,temperature,acceptance_ratio"
+            )?;
+        }
+        self.skipped += 1;
+        if self.skipped < self.interval {
+            return Ok(());
+        }
+        self.skipped = 0;
+        let optional = |x: Option<f64>| x.map_or(String::new(), |x| x.to_string());
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{}",
+            record.iteration,
+            record.elapsed.as_secs_f64(),
+            record.current,
+            record.best,
+            optional(record.temperature),
+            optional(record.acceptance_ratio),
+        )
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::annealing::annealing;
+    use crate::heuristics::hill_climb::hill_climb;
+    use crate::heuristics::{Metaheuristics, StateWrapper};
+    use crate::utils::random::XorShift;
+    use rand::{Rng, SeedableRng};
+
+    // -(x - 10)^2 を最大化し、探索の様子を記録する
+    struct Parabola {
+        rng: XorShift,
+        recorder: CsvRecorder<Vec<u8>>,
+        records: Vec<Record>,
+    }
+
+    impl Parabola {
+        fn new() -> Self {
+            Self {
+                rng: XorShift::seed_from_u64(1),
+                recorder: CsvRecorder::new(vec![], 2),
+                records: vec![],
+            }
+        }
+    }
+
+    impl Metaheuristics<i64> for Parabola {
+        fn evaluate(&mut self, st: &mut StateWrapper<i64>) -> f64 {
+            -((st.state() - 10) * (st.state() - 10)) as f64
+        }
+        fn generate(&mut self) -> StateWrapper<i64> {
+            StateWrapper::new(0)
+        }
+        fn neighbor(&mut self, st: &StateWrapper<i64>) -> StateWrapper<i64> {
+            StateWrapper::new(st.state() + if self.rng.gen_bool(0.5) { 1 } else { -1 })
+        }
+        fn observe(&mut self, record: &Record) {
+            self.recorder.record(record).unwrap();
+            self.records.push(record.clone());
+        }
+    }
+
+    #[test]
+    fn test_observe() {
+        let mut problem = Parabola::new();
+        let mut rng = XorShift::seed_from_u64(2);
+        annealing(&mut problem, 100, 10., 0.9, &mut rng);
+        assert_eq!(problem.records.len(), 100);
+        for (i, r) in problem.records.iter().enumerate() {
+            assert_eq!(r.iteration, i + 1);
+            assert!(r.current <= r.best);
+            assert!(r.temperature.is_some());
+            let ratio = r.acceptance_ratio.unwrap();
+            assert!((0. ..=1.).contains(&ratio));
+        }
+        let temperatures = problem
+            .records
+            .iter()
+            .map(|r| r.temperature.unwrap())
+            .collect::<Vec<_>>();
+        assert!(temperatures.windows(2).all(|w| w[0] > w[1]));
+
+        let mut problem = Parabola::new();
+        hill_climb(&mut problem, 50);
+        assert_eq!(problem.records.len(), 50);
+        assert!(problem.records.iter().all(|r| r.temperature.is_none()));
+        assert!(problem
+            .records
+            .iter()
+            .all(|r| r.current.to_bits() == r.best.to_bits()));
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut problem = Parabola::new();
+        hill_climb(&mut problem, 5);
+        let csv = String::from_utf8(problem.recorder.into_inner()).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "iteration,elapsed,current,best,temperature,acceptance_ratio"
+        );
+        let fields = lines[1].split(',').collect::<Vec<_>>();
+        assert_eq!(fields.len(), 6);
+        assert_eq!(fields[0], "2");
+        assert_eq!(fields[4], "");
+        assert!(fields[5].parse::<f64>().is_ok());
+        assert!(lines[2].starts_with("4,"));
+    }
+}
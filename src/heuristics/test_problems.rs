@@ -0,0 +1,26 @@
+use rand::Rng;
+
+/// 0..=100 の整数で、x = 30 に局所解 (50) を、x = 80 に最適解 (100) を持つ
+pub fn valley(x: i64) -> f64 {
+    let x = x as f64;
+    if x <= 30. {
+        x + 20.
+    } else if x <= 50. {
+        80. - x
+    } else if x <= 80. {
+        30. + (x - 50.) * 70. / 30.
+    } else {
+        180. - x
+    }
+}
+
+/// valley の近傍。0..=100 の範囲で x から 1 だけ動かす
+pub fn valley_neighbor<R: Rng>(x: i64, rng: &mut R) -> i64 {
+    let lo = if x > 0 { x - 1 } else { x + 1 };
+    let hi = if x < 100 { x + 1 } else { x - 1 };
+    if rng.gen_bool(0.5) {
+        lo
+    } else {
+        hi
+    }
+}
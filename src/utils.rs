@@ -1,10 +1,19 @@
+pub mod acted_monoids;
 pub mod algebraic_traits;
+pub mod bigint;
 pub mod bitset;
 pub mod bounds;
+pub mod combinatorics;
+pub mod fp;
+pub mod fraction;
 pub mod geometry;
+pub mod gf2;
 pub mod graph;
+pub mod linear_recurrence;
 pub mod math;
+pub mod matrix;
 pub mod modint;
+pub mod monoids;
 pub mod neboccoio;
 pub mod polynomial;
 pub mod prime;
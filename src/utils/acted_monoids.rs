@@ -0,0 +1,451 @@
+use crate::utils::algebraic_traits::{Associative, One, Pow, Zero};
+use crate::utils::monoids::{Bounded, Max, Min, Sum};
+use std::ops::{Add, Mul};
+
+// ------------ acted monoids start ------------
+
+// LazySegmentTree<T, E> や Treap<T, E> の (値, 作用) の組
+// 作用は pow(k) で長さ k の区間に作用させることを覚えておき、値との積で使う
+//
+// | 値     | 作用        | 型                 |
+// | ------ | ----------- | ------------------ |
+// | Sum    | RangeAdd    | AddSum<T>          |
+// | Min    | RangeAdd    | AddMin<T>          |
+// | Max    | RangeAdd    | AddMax<T>          |
+// | Sum    | RangeAssign | AssignSum<T>       |
+// | Min    | RangeAssign | AssignMin<T>       |
+// | Max    | RangeAssign | AssignMax<T>       |
+// | Sum    | RangeAffine | AffineSum<T>       |
+// | Max    | RangeClamp  | ClampMax<T>        |
+// | Min    | RangeClamp  | ClampMin<T>        |
+//
+// Sum と組む作用は i64 などの整数と StaticModInt で使える
+// (MapLazySegmentTree::<AddSum<i64>> のように組のまま MapMonoid としても使える)
+
+pub type AddSum<T> = (Sum<T>, RangeAdd<T>);
+pub type AddMin<T> = (Min<T>, RangeAdd<T>);
+pub type AddMax<T> = (Max<T>, RangeAdd<T>);
+pub type AssignSum<T> = (Sum<T>, RangeAssign<T>);
+pub type AssignMin<T> = (Min<T>, RangeAssign<T>);
+pub type AssignMax<T> = (Max<T>, RangeAssign<T>);
+pub type AffineSum<T> = (Sum<T>, RangeAffine<T>);
+pub type ClampMax<T> = (Max<T>, RangeClamp<T>);
+pub type ClampMin<T> = (Min<T>, RangeClamp<T>);
+
+/// 区間の各要素に x を足す
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RangeAdd<T> {
+    x: T,
+    len: u32,
+}
+
+impl<T> RangeAdd<T> {
+    pub fn new(x: T) -> Self {
+        Self { x, len: 1 }
+    }
+}
+
+impl<T: Add<Output = T>> Add for RangeAdd<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x)
+    }
+}
+
+impl<T: Add<Output = T> + Copy + PartialEq> Associative for RangeAdd<T> {}
+
+impl<T: Add<Output = T> + Copy + Zero> Zero for RangeAdd<T> {
+    fn zero() -> Self {
+        Self::new(T::zero())
+    }
+    fn is_zero(&self) -> bool {
+        self.x.is_zero()
+    }
+}
+
+impl<T: Copy> Pow for RangeAdd<T> {
+    fn pow(&self, p: u32) -> Self {
+        Self { x: self.x, len: p }
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: Add<Output = T> + Mul<Output = T> + From<u32>> Mul<RangeAdd<T>> for Sum<T> {
+    type Output = Self;
+    fn mul(self, f: RangeAdd<T>) -> Self {
+        Sum(self.0 + f.x * T::from(f.len))
+    }
+}
+
+/// 単位元 (番兵) には足さない
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: Add<Output = T> + Bounded + PartialEq> Mul<RangeAdd<T>> for Min<T> {
+    type Output = Self;
+    fn mul(self, f: RangeAdd<T>) -> Self {
+        if self.0 == T::max_value() {
+            self
+        } else {
+            Min(self.0 + f.x)
+        }
+    }
+}
+
+/// 単位元 (番兵) には足さない
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: Add<Output = T> + Bounded + PartialEq> Mul<RangeAdd<T>> for Max<T> {
+    type Output = Self;
+    fn mul(self, f: RangeAdd<T>) -> Self {
+        if self.0 == T::min_value() {
+            self
+        } else {
+            Max(self.0 + f.x)
+        }
+    }
+}
+
+/// 区間の各要素を x にする。None は何もしない
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RangeAssign<T> {
+    x: Option<T>,
+    len: u32,
+}
+
+impl<T> RangeAssign<T> {
+    pub fn new(x: T) -> Self {
+        Self { x: Some(x), len: 1 }
+    }
+}
+
+impl<T> Add for RangeAssign<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let x = if rhs.x.is_some() { rhs.x } else { self.x };
+        Self { x, len: 1 }
+    }
+}
+
+impl<T: Copy + PartialEq> Associative for RangeAssign<T> {}
+
+impl<T: Copy + PartialEq> Zero for RangeAssign<T> {
+    fn zero() -> Self {
+        Self { x: None, len: 1 }
+    }
+    fn is_zero(&self) -> bool {
+        self.x.is_none()
+    }
+}
+
+impl<T: Copy> Pow for RangeAssign<T> {
+    fn pow(&self, p: u32) -> Self {
+        Self { x: self.x, len: p }
+    }
+}
+
+impl<T: Mul<Output = T> + From<u32>> Mul<RangeAssign<T>> for Sum<T> {
+    type Output = Self;
+    fn mul(self, f: RangeAssign<T>) -> Self {
+        match f.x {
+            Some(x) => Sum(x * T::from(f.len)),
+            None => self,
+        }
+    }
+}
+
+impl<T> Mul<RangeAssign<T>> for Min<T> {
+    type Output = Self;
+    fn mul(self, f: RangeAssign<T>) -> Self {
+        match f.x {
+            Some(x) => Min(x),
+            None => self,
+        }
+    }
+}
+
+impl<T> Mul<RangeAssign<T>> for Max<T> {
+    type Output = Self;
+    fn mul(self, f: RangeAssign<T>) -> Self {
+        match f.x {
+            Some(x) => Max(x),
+            None => self,
+        }
+    }
+}
+
+/// 区間の各要素 x を a x + b にする
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RangeAffine<T> {
+    a: T,
+    b: T,
+    len: u32,
+}
+
+impl<T> RangeAffine<T> {
+    pub fn new(a: T, b: T) -> Self {
+        Self { a, b, len: 1 }
+    }
+}
+
+/// self を作用させてから rhs を作用させる
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: Add<Output = T> + Mul<Output = T> + Copy> Add for RangeAffine<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(rhs.a * self.a, rhs.a * self.b + rhs.b)
+    }
+}
+
+impl<T: Add<Output = T> + Mul<Output = T> + Copy + PartialEq> Associative for RangeAffine<T> {}
+
+impl<T: Add<Output = T> + Mul<Output = T> + Copy + Zero + One> Zero for RangeAffine<T> {
+    fn zero() -> Self {
+        Self::new(T::one(), T::zero())
+    }
+    fn is_zero(&self) -> bool {
+        self.a.is_one() && self.b.is_zero()
+    }
+}
+
+impl<T: Copy> Pow for RangeAffine<T> {
+    fn pow(&self, p: u32) -> Self {
+        Self {
+            a: self.a,
+            b: self.b,
+            len: p,
+        }
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: Add<Output = T> + Mul<Output = T> + From<u32>> Mul<RangeAffine<T>> for Sum<T> {
+    type Output = Self;
+    fn mul(self, f: RangeAffine<T>) -> Self {
+        Sum(f.a * self.0 + f.b * T::from(f.len))
+    }
+}
+
+/// 区間の各要素 x を min(max(x, lo), hi) にする
+/// chmin と chmax はこの形の作用の合成で閉じている
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RangeClamp<T> {
+    lo: T,
+    hi: T,
+}
+
+impl<T: Ord + Bounded> RangeClamp<T> {
+    pub fn new(lo: T, hi: T) -> Self {
+        assert!(lo <= hi, "lo must not exceed hi");
+        Self { lo, hi }
+    }
+
+    /// x を min(x, hi) にする
+    pub fn chmin(hi: T) -> Self {
+        Self::new(T::min_value(), hi)
+    }
+
+    /// x を max(x, lo) にする
+    pub fn chmax(lo: T) -> Self {
+        Self::new(lo, T::max_value())
+    }
+
+    pub fn apply(&self, x: T) -> T
+    where
+        T: Copy,
+    {
+        x.max(self.lo).min(self.hi)
+    }
+}
+
+impl<T: Ord + Bounded + Copy> Add for RangeClamp<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            lo: rhs.apply(self.lo),
+            hi: rhs.apply(self.hi),
+        }
+    }
+}
+
+impl<T: Ord + Bounded + Copy> Associative for RangeClamp<T> {}
+
+impl<T: Ord + Bounded + Copy> Zero for RangeClamp<T> {
+    fn zero() -> Self {
+        Self::new(T::min_value(), T::max_value())
+    }
+}
+
+impl<T: Copy> Pow for RangeClamp<T> {
+    fn pow(&self, _: u32) -> Self {
+        *self
+    }
+}
+
+/// 単調な作用なので最大値に作用させればよい。単位元 (番兵) はそのまま
+impl<T: Ord + Bounded + Copy> Mul<RangeClamp<T>> for Max<T> {
+    type Output = Self;
+    fn mul(self, f: RangeClamp<T>) -> Self {
+        if self.0 == T::min_value() {
+            self
+        } else {
+            Max(f.apply(self.0))
+        }
+    }
+}
+
+/// 単調な作用なので最小値に作用させればよい。単位元 (番兵) はそのまま
+impl<T: Ord + Bounded + Copy> Mul<RangeClamp<T>> for Min<T> {
+    type Output = Self;
+    fn mul(self, f: RangeClamp<T>) -> Self {
+        if self.0 == T::max_value() {
+            self
+        } else {
+            Min(f.apply(self.0))
+        }
+    }
+}
+
+// ------------ acted monoids end ------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::implicit_treap::Treap;
+    use crate::data_structures::segment_trees::lazy_segment_tree::LazySegmentTree;
+    use crate::data_structures::segment_trees::map_lazy_segment_tree::MapLazySegmentTree;
+    use crate::utils::modint::Mod998244353;
+    use crate::utils::random::XorShift;
+    use rand::{Rng, RngCore, SeedableRng};
+
+    type Mint = Mod998244353;
+
+    // 素朴な配列と LazySegmentTree, Treap, MapLazySegmentTree の結果を比べる
+    fn check<T, E, F, G>(init: Vec<T>, mut action: F, mut apply: G)
+    where
+        T: Zero + Add<Output = T> + Associative + Mul<E, Output = T> + std::fmt::Debug,
+        E: Zero + Add<Output = E> + Associative + Pow,
+        F: FnMut(&mut XorShift) -> E,
+        G: FnMut(&E, &mut T),
+    {
+        let mut rng = XorShift::seed_from_u64(1);
+        let n = init.len();
+        let mut a = init.clone();
+        let mut seg = LazySegmentTree::<T, E>::from(&init);
+        let mut treap = Treap::<T, E>::from(init.clone());
+        let mut map = MapLazySegmentTree::<(T, E)>::from(init);
+        for _ in 0..300 {
+            let l = rng.gen_range(0..n);
+            let r = rng.gen_range(l + 1..=n);
+            if rng.gen_bool(0.5) {
+                let e = action(&mut rng);
+                a[l..r].iter_mut().for_each(|x| apply(&e, x));
+                seg.update(l..r, e.clone());
+                treap.update(l..r, e.clone());
+                map.update(l..r, e);
+            } else {
+                let expected = a[l..r].iter().cloned().fold(T::zero(), |acc, x| acc + x);
+                assert_eq!(seg.fold(l..r), expected);
+                assert_eq!(treap.fold(l..r), expected);
+                assert_eq!(map.fold(l..r), expected);
+            }
+        }
+    }
+
+    fn values(n: usize) -> Vec<i64> {
+        let mut rng = XorShift::seed_from_u64(2);
+        (0..n).map(|_| rng.gen_range(-100..100)).collect()
+    }
+
+    #[test]
+    fn test_add() {
+        let a = values(37);
+        let add = |rng: &mut XorShift| RangeAdd::new(rng.gen_range(-10..10));
+        let apply = |f: &RangeAdd<i64>, x: &mut i64| *x += f.x;
+        check(
+            a.iter().map(|&x| Sum(x)).collect(),
+            add,
+            |f, v: &mut Sum<i64>| apply(f, &mut v.0),
+        );
+        check(
+            a.iter().map(|&x| Min(x)).collect(),
+            add,
+            |f, v: &mut Min<i64>| apply(f, &mut v.0),
+        );
+        check(
+            a.iter().map(|&x| Max(x)).collect(),
+            add,
+            |f, v: &mut Max<i64>| apply(f, &mut v.0),
+        );
+        check(
+            a.iter().map(|&x| Sum(Mint::from(x))).collect(),
+            |rng: &mut XorShift| RangeAdd::new(Mint::new(rng.next_u32())),
+            |f, v| v.0 += f.x,
+        );
+    }
+
+    #[test]
+    fn test_assign() {
+        let a = values(37);
+        let assign = |rng: &mut XorShift| RangeAssign::new(rng.gen_range(-10..10));
+        check(
+            a.iter().map(|&x| Sum(x)).collect(),
+            assign,
+            |f, v: &mut Sum<i64>| v.0 = f.x.unwrap(),
+        );
+        check(
+            a.iter().map(|&x| Min(x)).collect(),
+            assign,
+            |f, v: &mut Min<i64>| v.0 = f.x.unwrap(),
+        );
+        check(
+            a.iter().map(|&x| Max(x)).collect(),
+            assign,
+            |f, v: &mut Max<i64>| v.0 = f.x.unwrap(),
+        );
+        check(
+            a.iter().map(|&x| Sum(Mint::from(x))).collect(),
+            |rng: &mut XorShift| RangeAssign::new(Mint::new(rng.next_u32())),
+            |f, v| v.0 = f.x.unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_affine() {
+        let a = values(37);
+        check(
+            a.iter().map(|&x| Sum(x)).collect(),
+            |rng: &mut XorShift| RangeAffine::new(rng.gen_range(-1..=1), rng.gen_range(-10..10)),
+            |f, v: &mut Sum<i64>| v.0 = f.a * v.0 + f.b,
+        );
+        check(
+            a.iter().map(|&x| Sum(Mint::from(x))).collect(),
+            |rng: &mut XorShift| {
+                RangeAffine::new(Mint::new(rng.next_u32()), Mint::new(rng.next_u32()))
+            },
+            |f, v| v.0 = f.a * v.0 + f.b,
+        );
+    }
+
+    #[test]
+    fn test_clamp() {
+        let a = values(37);
+        let clamp = |rng: &mut XorShift| {
+            let x = rng.gen_range(-100..100);
+            if rng.gen_bool(0.5) {
+                RangeClamp::chmin(x)
+            } else {
+                RangeClamp::chmax(x)
+            }
+        };
+        check(
+            a.iter().map(|&x| Max(x)).collect(),
+            clamp,
+            |f, v: &mut Max<i64>| v.0 = f.apply(v.0),
+        );
+        check(
+            a.iter().map(|&x| Min(x)).collect(),
+            clamp,
+            |f, v: &mut Min<i64>| v.0 = f.apply(v.0),
+        );
+        let f = RangeClamp::chmax(3) + RangeClamp::chmin(5) + RangeClamp::chmin(1);
+        assert_eq!((f.apply(0), f.apply(4), f.apply(10)), (1, 1, 1));
+    }
+}
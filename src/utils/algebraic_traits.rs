@@ -66,6 +66,33 @@ pub trait One: Element {
     }
 }
 
+/// 作用付きモノイド
+/// 作用は区間の長さを受け取るので、区間和への加算なども M に長さを持たせずに書ける
+pub trait MapMonoid {
+    type M: Monoid;
+    type F: Element;
+    fn identity_map() -> Self::F;
+    /// 長さ len の区間の値 x に f を作用させる
+    fn mapping(f: &Self::F, x: &Self::M, len: usize) -> Self::M;
+    /// g を作用させてから f を作用させるのと同じ作用
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F;
+}
+
+/// Pow を使う作用 (LazySegmentTree, Treap の T, E) をそのまま使う
+impl<T: Monoid + Mul<E, Output = T>, E: Monoid + Pow> MapMonoid for (T, E) {
+    type M = T;
+    type F = E;
+    fn identity_map() -> E {
+        E::zero()
+    }
+    fn mapping(f: &E, x: &T, len: usize) -> T {
+        x.clone() * f.pow(len as u32)
+    }
+    fn composition(f: &E, g: &E) -> E {
+        g.clone() + f.clone()
+    }
+}
+
 macro_rules! impl_integer {
     ($($T:ty,)*) => {
         $(
@@ -98,4 +125,25 @@ impl_integer! {
     i8, i16, i32, i64, i128, isize,
     u8, u16, u32, u64, u128, usize,
 }
+
+// 浮動小数点数は誤差を無視して体とみなす
+macro_rules! impl_float {
+    ($($T:ty,)*) => {
+        $(
+            impl Associative for $T {}
+
+            impl Zero for $T {
+                fn zero() -> Self { 0. }
+            }
+
+            impl One for $T {
+                fn one() -> Self { 1. }
+            }
+        )*
+    };
+}
+
+impl_float! {
+    f32, f64,
+}
 // ------------ algebraic traits end ------------
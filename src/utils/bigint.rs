@@ -0,0 +1,633 @@
+use crate::utils::algebraic_traits::{Associative, One, Zero};
+use crate::utils::modint::{Modulus, StaticModInt, M998244353};
+use crate::utils::neboccoio::{Print, Scan, IO};
+use crate::utils::polynomial::{ArrayNTT, NTTFriendly, M167772161, M469762049};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
+
+// ------------ bigint start ------------
+
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+
+/// 多倍長の非負整数
+/// 10^9 進の下位桁から並べ、上位に 0 を持たない (0 は空列)
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    d: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl BigUint {
+    fn from_limbs(mut d: Vec<u32>) -> Self {
+        while d.last() == Some(&0) {
+            d.pop();
+        }
+        Self { d }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.d.is_empty()
+    }
+
+    pub fn pow(&self, mut e: u32) -> Self {
+        let mut x = self.clone();
+        let mut y = Self::from(1u32);
+        while e > 0 {
+            if e & 1 == 1 {
+                y = &y * &x;
+            }
+            e >>= 1;
+            if e > 0 {
+                x = &x * &x;
+            }
+        }
+        y
+    }
+
+    /// (self / rhs, self % rhs)
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        assert!(!rhs.is_zero(), "attempt to divide by zero");
+        if self < rhs {
+            return (Self::default(), self.clone());
+        }
+        if rhs.d.len() == 1 {
+            let (q, r) = self.div_rem_small(rhs.d[0]);
+            return (q, Self::from(r));
+        }
+        let (n, m) = (self.d.len(), rhs.d.len());
+        let v = &rhs.d;
+        let mut r = self.d.clone();
+        r.push(0);
+        let mut q = vec![0; n - m + 1];
+        let dt = v[m - 1] as u128 * BASE as u128 + v[m - 2] as u128;
+        for j in (0..=n - m).rev() {
+            let top = (r[j + m] as u128 * BASE as u128 + r[j + m - 1] as u128) * BASE as u128
+                + r[j + m - 2] as u128;
+            // 上 2 桁で割った商は真の商より高々 2 大きい
+            let mut qh = (top / dt).min(BASE as u128 - 1) as i64;
+            loop {
+                let mut borrow = 0i64;
+                let mut w = r[j..=j + m].to_vec();
+                for i in 0..=m {
+                    let sub = if i < m { qh * v[i] as i64 } else { 0 } + borrow;
+                    let mut t = w[i] as i64 - sub % BASE as i64;
+                    borrow = sub / BASE as i64;
+                    if t < 0 {
+                        t += BASE as i64;
+                        borrow += 1;
+                    }
+                    w[i] = t as u32;
+                }
+                if borrow == 0 {
+                    r[j..=j + m].copy_from_slice(&w);
+                    break;
+                }
+                qh -= 1;
+            }
+            q[j] = qh as u32;
+        }
+        (Self::from_limbs(q), Self::from_limbs(r))
+    }
+
+    fn div_rem_small(&self, rhs: u32) -> (Self, u32) {
+        let mut q = vec![0; self.d.len()];
+        let mut r = 0u64;
+        for i in (0..self.d.len()).rev() {
+            let cur = r * BASE + self.d[i] as u64;
+            q[i] = (cur / rhs as u64) as u32;
+            r = cur % rhs as u64;
+        }
+        (Self::from_limbs(q), r as u32)
+    }
+
+    fn add_ref(&self, rhs: &Self) -> Self {
+        let (a, b) = if self.d.len() >= rhs.d.len() {
+            (&self.d, &rhs.d)
+        } else {
+            (&rhs.d, &self.d)
+        };
+        let mut d = Vec::with_capacity(a.len() + 1);
+        let mut carry = 0;
+        for (i, &x) in a.iter().enumerate() {
+            let mut t = x + b.get(i).unwrap_or(&0) + carry;
+            carry = 0;
+            if t >= BASE as u32 {
+                t -= BASE as u32;
+                carry = 1;
+            }
+            d.push(t);
+        }
+        if carry > 0 {
+            d.push(carry);
+        }
+        Self { d }
+    }
+
+    /// self - rhs (self >= rhs)
+    fn sub_ref(&self, rhs: &Self) -> Self {
+        assert!(*self >= *rhs, "attempt to subtract with overflow");
+        let mut d = Vec::with_capacity(self.d.len());
+        let mut borrow = 0;
+        for i in 0..self.d.len() {
+            let mut t = self.d[i] as i64 - *rhs.d.get(i).unwrap_or(&0) as i64 - borrow;
+            borrow = 0;
+            if t < 0 {
+                t += BASE as i64;
+                borrow = 1;
+            }
+            d.push(t as u32);
+        }
+        Self::from_limbs(d)
+    }
+
+    fn mul_ref(&self, rhs: &Self) -> Self {
+        if self.is_zero() || rhs.is_zero() {
+            return Self::default();
+        }
+        if self.d.len().min(rhs.d.len()) <= 32 {
+            let mut c = vec![0u64; self.d.len() + rhs.d.len()];
+            for (i, &a) in self.d.iter().enumerate() {
+                let mut carry = 0;
+                for (j, &b) in rhs.d.iter().enumerate() {
+                    let t = c[i + j] + a as u64 * b as u64 + carry;
+                    c[i + j] = t % BASE;
+                    carry = t / BASE;
+                }
+                c[i + rhs.d.len()] += carry;
+            }
+            return Self::from_limbs(c.into_iter().map(|x| x as u32).collect());
+        }
+        let c1 = convolution::<M998244353>(&self.d, &rhs.d);
+        let c2 = convolution::<M167772161>(&self.d, &rhs.d);
+        let c3 = convolution::<M469762049>(&self.d, &rhs.d);
+        let mut d = Vec::with_capacity(c1.len() + 2);
+        let mut carry = 0u128;
+        for i in 0..c1.len() {
+            carry += garner(c1[i], c2[i], c3[i]);
+            d.push((carry % BASE as u128) as u32);
+            carry /= BASE as u128;
+        }
+        while carry > 0 {
+            d.push((carry % BASE as u128) as u32);
+            carry /= BASE as u128;
+        }
+        Self::from_limbs(d)
+    }
+}
+
+fn convolution<M: NTTFriendly>(a: &[u32], b: &[u32]) -> Vec<StaticModInt<M>> {
+    let f = a.iter().map(|&x| StaticModInt::new(x)).collect::<Vec<_>>();
+    let g = b.iter().map(|&x| StaticModInt::new(x)).collect::<Vec<_>>();
+    f.multiply(&g)
+}
+
+/// 3 つの素数での剰余から畳み込みの値を復元する
+fn garner(
+    r1: StaticModInt<M998244353>,
+    r2: StaticModInt<M167772161>,
+    r3: StaticModInt<M469762049>,
+) -> u128 {
+    let m1 = M998244353::MODULUS as u64;
+    let m2 = M167772161::MODULUS as u64;
+    let x1 = r1.to_inner() as u64;
+    let x2 = (r2 - StaticModInt::from(x1)) / StaticModInt::from(m1);
+    let x2 = x2.to_inner() as u64;
+    let x3 = (r3 - StaticModInt::from(x1) - StaticModInt::from(x2) * StaticModInt::from(m1))
+        / StaticModInt::from(m1 * m2);
+    x1 as u128 + x2 as u128 * m1 as u128 + x3.to_inner() as u128 * (m1 * m2) as u128
+}
+
+impl From<u32> for BigUint {
+    fn from(x: u32) -> Self {
+        Self::from(x as u64)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut x: u64) -> Self {
+        let mut d = vec![];
+        while x > 0 {
+            d.push((x % BASE) as u32);
+            x /= BASE;
+        }
+        Self { d }
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(x: usize) -> Self {
+        Self::from(x as u64)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.as_bytes();
+        if s.is_empty() || !s.iter().all(u8::is_ascii_digit) {
+            return Err(ParseBigIntError);
+        }
+        let d = s
+            .rchunks(BASE_DIGITS)
+            .map(|c| c.iter().fold(0, |acc, &b| acc * 10 + (b - b'0') as u32))
+            .collect();
+        Ok(Self::from_limbs(d))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.d.last() {
+            None => write!(f, "0"),
+            Some(top) => {
+                let mut s = top.to_string();
+                for x in self.d.iter().rev().skip(1) {
+                    s.push_str(&format!("{:09}", x));
+                }
+                f.pad_integral(true, "", &s)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.d
+            .len()
+            .cmp(&other.d.len())
+            .then_with(|| self.d.iter().rev().cmp(other.d.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// 多倍長整数
+/// 割り算は i64 と同じく 0 に向かって切り捨てる
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    /// 負かどうか (0 は負でない)
+    neg: bool,
+    abs: BigUint,
+}
+
+impl BigInt {
+    fn new(neg: bool, abs: BigUint) -> Self {
+        Self {
+            neg: neg && !abs.is_zero(),
+            abs,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.abs.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.neg
+    }
+
+    pub fn abs(&self) -> BigUint {
+        self.abs.clone()
+    }
+
+    pub fn pow(&self, e: u32) -> Self {
+        Self::new(self.neg && e & 1 == 1, self.abs.pow(e))
+    }
+
+    /// (self / rhs, self % rhs)
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let (q, r) = self.abs.div_rem(&rhs.abs);
+        (Self::new(self.neg != rhs.neg, q), Self::new(self.neg, r))
+    }
+
+    fn add_ref(&self, rhs: &Self) -> Self {
+        if self.neg == rhs.neg {
+            Self::new(self.neg, self.abs.add_ref(&rhs.abs))
+        } else if self.abs >= rhs.abs {
+            Self::new(self.neg, self.abs.sub_ref(&rhs.abs))
+        } else {
+            Self::new(rhs.neg, rhs.abs.sub_ref(&self.abs))
+        }
+    }
+
+    fn sub_ref(&self, rhs: &Self) -> Self {
+        self.add_ref(&-rhs)
+    }
+
+    fn mul_ref(&self, rhs: &Self) -> Self {
+        Self::new(self.neg != rhs.neg, self.abs.mul_ref(&rhs.abs))
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(abs: BigUint) -> Self {
+        Self::new(false, abs)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(x: i64) -> Self {
+        Self::new(x < 0, BigUint::from(x.wrapping_abs() as u64))
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(x: u64) -> Self {
+        Self::new(false, BigUint::from(x))
+    }
+}
+
+impl From<usize> for BigInt {
+    fn from(x: usize) -> Self {
+        Self::new(false, BigUint::from(x))
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (neg, s) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        Ok(Self::new(neg, s.parse()?))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.neg, "", &self.abs.to_string())
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.neg, other.neg) {
+            (false, false) => self.abs.cmp(&other.abs),
+            (true, true) => other.abs.cmp(&self.abs),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(!self.neg, self.abs)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.neg, self.abs.clone())
+    }
+}
+
+macro_rules! impl_bigint_ops {
+    ($t:ident, $(impl $imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident, $f:expr;)*) => {
+        $(
+            impl<'a> $imp<&'a $t> for &'a $t {
+                type Output = $t;
+                fn $method(self, rhs: &$t) -> $t {
+                    $f(self, rhs)
+                }
+            }
+
+            impl $imp for $t {
+                type Output = $t;
+                fn $method(self, rhs: $t) -> $t {
+                    $f(&self, &rhs)
+                }
+            }
+
+            impl<'a> $imp<&'a $t> for $t {
+                type Output = $t;
+                fn $method(self, rhs: &$t) -> $t {
+                    $f(&self, rhs)
+                }
+            }
+
+            impl $imp_assign for $t {
+                fn $method_assign(&mut self, rhs: $t) {
+                    *self = $f(self, &rhs);
+                }
+            }
+
+            impl<'a> $imp_assign<&'a $t> for $t {
+                fn $method_assign(&mut self, rhs: &$t) {
+                    *self = $f(self, rhs);
+                }
+            }
+        )*
+    };
+}
+
+impl_bigint_ops! {
+    BigUint,
+    impl Add, add, AddAssign, add_assign, BigUint::add_ref;
+    impl Sub, sub, SubAssign, sub_assign, BigUint::sub_ref;
+    impl Mul, mul, MulAssign, mul_assign, BigUint::mul_ref;
+    impl Div, div, DivAssign, div_assign, |a: &BigUint, b| a.div_rem(b).0;
+    impl Rem, rem, RemAssign, rem_assign, |a: &BigUint, b| a.div_rem(b).1;
+}
+
+impl_bigint_ops! {
+    BigInt,
+    impl Add, add, AddAssign, add_assign, BigInt::add_ref;
+    impl Sub, sub, SubAssign, sub_assign, BigInt::sub_ref;
+    impl Mul, mul, MulAssign, mul_assign, BigInt::mul_ref;
+    impl Div, div, DivAssign, div_assign, |a: &BigInt, b| a.div_rem(b).0;
+    impl Rem, rem, RemAssign, rem_assign, |a: &BigInt, b| a.div_rem(b).1;
+}
+
+impl Associative for BigUint {}
+
+impl Zero for BigUint {
+    fn zero() -> Self {
+        Self::default()
+    }
+    fn is_zero(&self) -> bool {
+        self.d.is_empty()
+    }
+}
+
+impl One for BigUint {
+    fn one() -> Self {
+        Self::from(1u32)
+    }
+}
+
+impl Associative for BigInt {}
+
+impl Zero for BigInt {
+    fn zero() -> Self {
+        Self::default()
+    }
+    fn is_zero(&self) -> bool {
+        self.abs.is_zero()
+    }
+}
+
+impl One for BigInt {
+    fn one() -> Self {
+        Self::from(1i64)
+    }
+}
+
+impl Scan for BigUint {
+    type Output = Self;
+    fn scan(io: &mut IO) -> Self {
+        String::scan(io).parse().unwrap()
+    }
+}
+
+impl Scan for BigInt {
+    type Output = Self;
+    fn scan(io: &mut IO) -> Self {
+        String::scan(io).parse().unwrap()
+    }
+}
+
+impl Print for BigUint {
+    fn print(w: &mut IO, x: Self) {
+        w.print(x.to_string());
+    }
+}
+
+impl Print for BigInt {
+    fn print(w: &mut IO, x: Self) {
+        w.print(x.to_string());
+    }
+}
+
+// ------------ bigint end ------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::algebraic_traits::Ring;
+    use crate::utils::random::XorShift;
+    use rand::{Rng, SeedableRng};
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_print() {
+        for s in &[
+            "0",
+            "1",
+            "-1",
+            "999999999",
+            "1000000000",
+            "-123456789012345678901234567890",
+        ] {
+            assert_eq!(big(s).to_string(), *s);
+        }
+        assert_eq!(big("-0"), BigInt::zero());
+        assert_eq!(big("+000123").to_string(), "123");
+        assert_eq!(format!("{:>5}", big("-12")), "  -12");
+        assert!("12a".parse::<BigInt>().is_err());
+        assert!("".parse::<BigUint>().is_err());
+        assert!("-5".parse::<BigUint>().is_err());
+    }
+
+    #[test]
+    fn test_small_values() {
+        let mut rng = XorShift::seed_from_u64(1);
+        for _ in 0..10000 {
+            let a = rng.gen_range(-1_000_000_000_000i64..1_000_000_000_000);
+            let b = rng.gen_range(-1_000_000i64..1_000_000);
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            assert_eq!(&x + &y, BigInt::from(a + b));
+            assert_eq!(&x - &y, BigInt::from(a - b));
+            assert_eq!(&x * &y, BigInt::from(a * b));
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+            if b != 0 {
+                assert_eq!(&x / &y, BigInt::from(a / b));
+                assert_eq!(&x % &y, BigInt::from(a % b));
+            }
+        }
+    }
+
+    fn random_biguint(rng: &mut XorShift, limbs: usize) -> BigUint {
+        BigUint::from_limbs((0..limbs).map(|_| rng.gen_range(0..BASE as u32)).collect())
+    }
+
+    #[test]
+    fn test_mul_div() {
+        let mut rng = XorShift::seed_from_u64(2);
+        for &(n, m) in &[(1, 1), (5, 3), (40, 2), (40, 33), (300, 200), (1000, 1)] {
+            for _ in 0..10 {
+                let a = random_biguint(&mut rng, n);
+                let b = random_biguint(&mut rng, m) + BigUint::one();
+                let c = random_biguint(&mut rng, m);
+                let (q, r) = a.div_rem(&b);
+                assert!(r < b);
+                assert_eq!(&q * &b + &r, a);
+                // 分配法則で NTT と筆算を比べる
+                assert_eq!(&a * &(&b + &c), &a * &b + &a * &c);
+            }
+        }
+        let x = BigUint::from(BASE - 1).pow(100);
+        let y = BigUint::from(BASE - 1).pow(40);
+        assert_eq!(&x / &y, BigUint::from(BASE - 1).pow(60));
+        assert!((&x % &y).is_zero());
+    }
+
+    #[test]
+    fn test_pow() {
+        assert_eq!(
+            BigUint::from(2u32).pow(200).to_string(),
+            "1606938044258990275541962092341162602522202993782792835301376"
+        );
+        assert_eq!(big("-3").pow(3), big("-27"));
+        // 2^4423 - 1 はメルセンヌ素数で、10 進 1332 桁
+        let m = BigUint::from(2u32).pow(4423) - BigUint::one();
+        assert_eq!(m.to_string().len(), 1332);
+        assert_eq!((&m * &m).to_string().len(), 2663);
+    }
+
+    fn ring_sum<T: Ring>(v: Vec<T>) -> T {
+        v.into_iter().fold(T::zero(), |acc, x| acc + x)
+    }
+
+    #[test]
+    fn test_ring() {
+        let v = vec![big("10000000000000000000000"), big("-1"), -BigInt::one()];
+        assert_eq!(ring_sum(v), big("9999999999999999999998"));
+    }
+}
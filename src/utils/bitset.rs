@@ -1,4 +1,4 @@
-use std::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, Shr};
 
 #[derive(Clone)]
 pub struct BitSet {
@@ -12,8 +12,13 @@ impl BitSet {
         BitSet { data, size }
     }
 
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
     pub fn fill(&mut self) {
         self.data.iter_mut().for_each(|x| *x = 0xffff_ffff);
+        self.trim();
     }
 
     pub fn access(&self, pos: usize) -> bool {
@@ -39,6 +44,29 @@ impl BitSet {
             .collect::<Vec<u64>>()
     }
 
+    pub fn count_ones(&self) -> usize {
+        self.data.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    /// 立っているビットがあるか
+    pub fn any(&self) -> bool {
+        self.data.iter().any(|&x| x != 0)
+    }
+
+    /// 立っているビットのうち最上位のもの
+    pub fn highest_one(&self) -> Option<usize> {
+        self.data
+            .iter()
+            .rposition(|&x| x != 0)
+            .map(|i| (i << 5) + 31 - self.data[i].leading_zeros() as usize)
+    }
+
+    /// size 以上の位置のビットを落とす
+    fn trim(&mut self) {
+        let last = self.data.len() - 1;
+        self.data[last] &= (1 << (self.size & 31)) - 1;
+    }
+
     fn resize(&mut self, l: usize) {
         if self.size > l {
             return;
@@ -90,6 +118,7 @@ impl Not for BitSet {
         for u in self.data.iter_mut() {
             *u = !*u;
         }
+        self.trim();
         self
     }
 }
@@ -133,11 +162,33 @@ impl Shl<usize> for BitSet {
             self.data[i] = u & !mask | r;
             r = u & mask;
         }
-        self.data[n - 1] &= (1 << (self.size & 31)) - 1;
+        self.trim();
         self
     }
 }
 
+// 右辺の方が長い場合、はみ出た部分は無視する
+macro_rules! impl_bit_assign {
+    ($(impl $imp:ident, $method:ident, $op:tt;)*) => {
+        $(
+            impl $imp<&BitSet> for BitSet {
+                fn $method(&mut self, rhs: &BitSet) {
+                    for (u, v) in self.data.iter_mut().zip(rhs.data.iter()) {
+                        *u $op v;
+                    }
+                    self.trim();
+                }
+            }
+        )*
+    };
+}
+
+impl_bit_assign! {
+    impl BitAndAssign, bitand_assign, &=;
+    impl BitOrAssign, bitor_assign, |=;
+    impl BitXorAssign, bitxor_assign, ^=;
+}
+
 #[cfg(test)]
 mod tests {
     use super::BitSet;
@@ -207,4 +258,25 @@ mod tests {
         a = a << 600;
         assert_eq!(a.collect(), vec![]);
     }
+
+    #[test]
+    fn test_words() {
+        let mut a = BitSet::new(70);
+        a.fill();
+        assert_eq!(a.count_ones(), 70);
+        assert_eq!(a.highest_one(), Some(69));
+        a = a >> 1;
+        assert_eq!(a.highest_one(), Some(68));
+        let mut b = BitSet::new(200);
+        b.set(3, true);
+        b.set(150, true);
+        a ^= &b;
+        assert_eq!(a.count_ones(), 68);
+        assert!(!a.access(3));
+        a &= &b;
+        assert!(!a.any());
+        assert_eq!(a.highest_one(), None);
+        a |= &b;
+        assert_eq!(a.collect(), vec![3]);
+    }
 }
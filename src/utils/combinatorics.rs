@@ -0,0 +1,434 @@
+use crate::utils::algebraic_traits::{Field, Ring, SemiRing};
+use crate::utils::math::{chinese_remainder, modinv, modpow};
+use crate::utils::prime::factorize;
+
+// ------------ combinatorics start ------------
+
+/// 階乗とその逆元の表
+/// T は StaticModInt や DynamicModInt などの素数を法とする体で、表の大きさは法より小さくする
+pub struct Factorials<T> {
+    fact: Vec<T>,
+    inv_fact: Vec<T>,
+}
+
+impl<T: Field + Copy + From<usize>> Factorials<T> {
+    /// n! までを計算する
+    pub fn new(n: usize) -> Self {
+        let mut fact = vec![T::one(); n + 1];
+        for i in 1..=n {
+            fact[i] = fact[i - 1] * T::from(i);
+        }
+        let mut inv_fact = vec![T::one(); n + 1];
+        inv_fact[n] = T::one() / fact[n];
+        for i in (1..=n).rev() {
+            inv_fact[i - 1] = inv_fact[i] * T::from(i);
+        }
+        Self { fact, inv_fact }
+    }
+
+    pub fn fact(&self, n: usize) -> T {
+        self.fact[n]
+    }
+
+    pub fn inv_fact(&self, n: usize) -> T {
+        self.inv_fact[n]
+    }
+
+    /// 1 / n
+    pub fn inv(&self, n: usize) -> T {
+        assert!(n > 0, "zero has no inverse");
+        self.inv_fact[n] * self.fact[n - 1]
+    }
+
+    /// nPr
+    pub fn perm(&self, n: usize, r: usize) -> T {
+        if r > n {
+            T::zero()
+        } else {
+            self.fact[n] * self.inv_fact[n - r]
+        }
+    }
+
+    /// nCr
+    pub fn binom(&self, n: usize, r: usize) -> T {
+        if r > n {
+            T::zero()
+        } else {
+            self.fact[n] * self.inv_fact[r] * self.inv_fact[n - r]
+        }
+    }
+
+    /// 重複組合せ nHr
+    pub fn homo(&self, n: usize, r: usize) -> T {
+        if n == 0 {
+            if r == 0 {
+                T::one()
+            } else {
+                T::zero()
+            }
+        } else {
+            self.binom(n + r - 1, r)
+        }
+    }
+
+    /// (k_1 + k_2 + ...)! / (k_1! k_2! ...)
+    pub fn multinomial(&self, k: &[usize]) -> T {
+        let n = k.iter().sum::<usize>();
+        k.iter()
+            .fold(self.fact[n], |acc, &k| acc * self.inv_fact[k])
+    }
+
+    /// n 番目のカタラン数。表は 2n まで必要
+    pub fn catalan(&self, n: usize) -> T {
+        self.fact[2 * n] * self.inv_fact[n + 1] * self.inv_fact[n]
+    }
+
+    /// 第 2 種スターリング数 S(n, k) を O(k log n) で求める。表は k まで必要
+    pub fn stirling_second(&self, n: usize, k: usize) -> T {
+        let pow = |x: T, mut e: usize| {
+            let (mut x, mut y) = (x, T::one());
+            while e > 0 {
+                if e & 1 == 1 {
+                    y *= x;
+                }
+                x = x * x;
+                e >>= 1;
+            }
+            y
+        };
+        let mut res = T::zero();
+        for i in 0..=k {
+            let t = self.binom(k, i) * pow(T::from(i), n);
+            if (k - i) & 1 == 0 {
+                res += t;
+            } else {
+                res += -t;
+            }
+        }
+        res * self.inv_fact[k]
+    }
+}
+
+/// 符号なし第 1 種スターリング数 s(i, j) (i, j <= n) の表
+pub fn stirling_first_table<T: SemiRing + Copy + From<usize>>(n: usize) -> Vec<Vec<T>> {
+    let mut s = vec![vec![T::zero(); n + 1]; n + 1];
+    s[0][0] = T::one();
+    for i in 1..=n {
+        for j in 1..=i {
+            s[i][j] = s[i - 1][j - 1] + T::from(i - 1) * s[i - 1][j];
+        }
+    }
+    s
+}
+
+/// 第 2 種スターリング数 S(i, j) (i, j <= n) の表
+pub fn stirling_second_table<T: SemiRing + Copy + From<usize>>(n: usize) -> Vec<Vec<T>> {
+    let mut s = vec![vec![T::zero(); n + 1]; n + 1];
+    s[0][0] = T::one();
+    for i in 1..=n {
+        for j in 1..=i {
+            s[i][j] = s[i - 1][j - 1] + T::from(j) * s[i - 1][j];
+        }
+    }
+    s
+}
+
+/// ベル数 B(0), ..., B(n) をベルの三角形で求める
+pub fn bell_numbers<T: SemiRing + Copy>(n: usize) -> Vec<T> {
+    let mut res = vec![T::one()];
+    let mut row = vec![T::one()];
+    for _ in 0..n {
+        let mut next = Vec::with_capacity(row.len() + 1);
+        next.push(*row.last().unwrap());
+        for &x in &row {
+            let y = *next.last().unwrap() + x;
+            next.push(y);
+        }
+        res.push(next[0]);
+        row = next;
+    }
+    res.truncate(n + 1);
+    res
+}
+
+/// 分割数 p(0), ..., p(n) を五角数定理で O(n sqrt n) で求める
+pub fn partition_numbers<T: Ring + Copy>(n: usize) -> Vec<T> {
+    let mut p = vec![T::zero(); n + 1];
+    p[0] = T::one();
+    for i in 1..=n {
+        let mut sum = T::zero();
+        for k in 1.. {
+            let a = k * (3 * k - 1) / 2;
+            if a > i {
+                break;
+            }
+            let b = a + k;
+            let mut t = p[i - a];
+            if b <= i {
+                t += p[i - b];
+            }
+            if k & 1 == 1 {
+                sum += t;
+            } else {
+                sum += -t;
+            }
+        }
+        p[i] = sum;
+    }
+    p
+}
+
+/// 小さい素数 p を法とする二項係数 (Lucas の定理)
+pub struct Lucas {
+    p: u64,
+    fact: Vec<u64>,
+    inv_fact: Vec<u64>,
+}
+
+impl Lucas {
+    pub fn new(p: u32) -> Self {
+        let p = p as u64;
+        let mut fact = vec![1; p as usize];
+        for i in 1..p as usize {
+            fact[i] = fact[i - 1] * i as u64 % p;
+        }
+        let inv_fact = fact
+            .iter()
+            .map(|&f| modpow(f as i64, p as i64 - 2, p as i64) as u64)
+            .collect();
+        Self { p, fact, inv_fact }
+    }
+
+    /// nCr mod p
+    pub fn binom(&self, mut n: u64, mut r: u64) -> u64 {
+        let p = self.p;
+        let mut res = 1 % p;
+        while r > 0 {
+            let (a, b) = ((n % p) as usize, (r % p) as usize);
+            if a < b {
+                return 0;
+            }
+            res = res * self.fact[a] % p * self.inv_fact[b] % p * self.inv_fact[a - b] % p;
+            n /= p;
+            r /= p;
+        }
+        res
+    }
+}
+
+/// 素数冪 p^e を法とする二項係数
+struct PrimePowerBinom {
+    p: u64,
+    e: u32,
+    q: u64,
+    /// p と互いに素な i 以下の自然数の積
+    prod: Vec<u64>,
+}
+
+impl PrimePowerBinom {
+    fn new(p: u64, e: u32) -> Self {
+        let q = p.pow(e);
+        let mut prod = vec![1 % q; q as usize];
+        for i in 1..q as usize {
+            prod[i] = if i as u64 % p == 0 {
+                prod[i - 1]
+            } else {
+                prod[i - 1] * i as u64 % q
+            };
+        }
+        Self { p, e, q, prod }
+    }
+
+    /// n! から p を除いた積 mod q
+    fn fact_without_p(&self, mut n: u64) -> u64 {
+        let q = self.q;
+        let mut res = 1 % q;
+        while n > 0 {
+            let cycle = modpow(self.prod[q as usize - 1] as i64, (n / q) as i64, q as i64);
+            res = res * cycle as u64 % q * self.prod[(n % q) as usize] % q;
+            n /= self.p;
+        }
+        res
+    }
+
+    /// n! が p で割り切れる回数
+    fn legendre(&self, mut n: u64) -> u64 {
+        let mut res = 0;
+        while n > 0 {
+            n /= self.p;
+            res += n;
+        }
+        res
+    }
+
+    fn binom(&self, n: u64, r: u64) -> u64 {
+        let q = self.q;
+        let k = self.legendre(n) - self.legendre(r) - self.legendre(n - r);
+        if k >= self.e as u64 {
+            return 0;
+        }
+        let den = self.fact_without_p(r) * self.fact_without_p(n - r) % q;
+        let res = self.fact_without_p(n) * modinv(den as i64, q as i64) as u64 % q;
+        res * self.p.pow(k as u32) % q
+    }
+}
+
+/// 任意の法 m での二項係数
+/// m を素数冪に分解し、それぞれで求めた値を中国剰余定理で復元する
+/// 前計算に m の各素数冪の大きさだけ時間と空間がかかる
+pub struct ArbitraryModBinom {
+    m: u64,
+    parts: Vec<PrimePowerBinom>,
+}
+
+impl ArbitraryModBinom {
+    pub fn new(m: u32) -> Self {
+        assert!(m > 0, "modulus must be positive");
+        let parts = factorize(m as i64)
+            .into_iter()
+            .map(|(p, e)| PrimePowerBinom::new(p as u64, e as u32))
+            .collect();
+        Self { m: m as u64, parts }
+    }
+
+    /// nCr mod m
+    pub fn binom(&self, n: u64, r: u64) -> u64 {
+        if r > n {
+            return 0;
+        }
+        let l = self
+            .parts
+            .iter()
+            .map(|part| (part.binom(n, r) as i64, part.q as i64))
+            .collect::<Vec<_>>();
+        let (x, _) = chinese_remainder(&l).unwrap();
+        x as u64 % self.m
+    }
+}
+
+// ------------ combinatorics end ------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::modint::{DynamicModInt, Mod998244353};
+
+    type Mint = Mod998244353;
+
+    fn pascal(n: usize, m: u64) -> Vec<Vec<u64>> {
+        let mut c = vec![vec![0; n + 1]; n + 1];
+        for i in 0..=n {
+            c[i][0] = 1 % m;
+            for j in 1..=i {
+                c[i][j] = (c[i - 1][j - 1] + c[i - 1][j]) % m;
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn test_factorials() {
+        let f = Factorials::<Mint>::new(200);
+        let c = pascal(100, 998_244_353);
+        for (n, row) in c.iter().enumerate() {
+            for (r, &x) in row.iter().enumerate().take(n + 1) {
+                assert_eq!(f.binom(n, r).to_inner(), x as u32);
+            }
+            assert_eq!(f.binom(n, n + 1).to_inner(), 0);
+        }
+        assert_eq!(f.perm(5, 2), Mint::new(20));
+        assert_eq!(f.homo(3, 2), Mint::new(6));
+        assert_eq!(f.inv(7) * Mint::new(7), Mint::new(1));
+        assert_eq!(f.multinomial(&[2, 1, 1]), Mint::new(12));
+        let catalan = [1, 1, 2, 5, 14, 42, 132, 429];
+        for (n, &c) in catalan.iter().enumerate() {
+            assert_eq!(f.catalan(n), Mint::new(c));
+        }
+
+        DynamicModInt::set_modulus(1_000_000_007);
+        let f = Factorials::<DynamicModInt>::new(100);
+        let c = pascal(100, 1_000_000_007);
+        assert_eq!(f.binom(100, 50).to_inner() as u64, c[100][50]);
+        assert_eq!(f.fact(10).to_inner(), 3_628_800);
+    }
+
+    #[test]
+    fn test_stirling() {
+        let s1 = stirling_first_table::<Mint>(10);
+        assert_eq!(s1[4][2], Mint::new(11));
+        assert_eq!(s1[6][3], Mint::new(225));
+        // 行の和は n!
+        let sum = s1[10].iter().fold(Mint::new(0), |acc, &x| acc + x);
+        assert_eq!(sum, Mint::new(3_628_800));
+
+        let s2 = stirling_second_table::<Mint>(30);
+        assert_eq!(s2[4][2], Mint::new(7));
+        assert_eq!(s2[10][5], Mint::new(42525));
+        let f = Factorials::<Mint>::new(30);
+        for (n, row) in s2.iter().enumerate() {
+            for (k, &x) in row.iter().enumerate().take(n + 1) {
+                assert_eq!(f.stirling_second(n, k), x);
+            }
+        }
+
+        // ベル数は第 2 種スターリング数の行の和
+        let bell = bell_numbers::<Mint>(30);
+        assert_eq!(bell.len(), 31);
+        for (row, &b) in s2.iter().zip(&bell) {
+            let sum = row.iter().fold(Mint::new(0), |acc, &x| acc + x);
+            assert_eq!(b, sum);
+        }
+        assert_eq!(
+            &bell[..6],
+            &[1, 1, 2, 5, 15, 52]
+                .iter()
+                .map(|&x| Mint::new(x))
+                .collect::<Vec<_>>()[..]
+        );
+    }
+
+    #[test]
+    fn test_partition() {
+        let p = partition_numbers::<Mint>(100);
+        let expected = [1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42];
+        for (i, &x) in expected.iter().enumerate() {
+            assert_eq!(p[i], Mint::new(x));
+        }
+        assert_eq!(p[100], Mint::new(190_569_292));
+    }
+
+    #[test]
+    fn test_lucas() {
+        for &p in &[2, 3, 5, 7, 13] {
+            let lucas = Lucas::new(p);
+            let c = pascal(100, p as u64);
+            for (n, row) in c.iter().enumerate() {
+                for (r, &x) in row.iter().enumerate().take(n + 1) {
+                    assert_eq!(lucas.binom(n as u64, r as u64), x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_mod() {
+        for &m in &[1, 2, 12, 36, 100, 720, 1024, 999, 30_030] {
+            let binom = ArbitraryModBinom::new(m);
+            let c = pascal(150, m as u64);
+            for (n, row) in c.iter().enumerate() {
+                for (r, &x) in row.iter().enumerate().take(n + 1) {
+                    assert_eq!(binom.binom(n as u64, r as u64), x, "{} {} {}", n, r, m);
+                }
+            }
+            assert_eq!(binom.binom(3, 5), 0);
+        }
+        // 素数なら Lucas と一致する
+        let (lucas, binom) = (Lucas::new(10_007), ArbitraryModBinom::new(10_007));
+        for &(n, r) in &[
+            (1_000_000_000_000_000_000, 12_345_678_901),
+            (123_456_789, 98_765),
+        ] {
+            assert_eq!(lucas.binom(n, r), binom.binom(n, r));
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::utils::algebraic_traits::{Associative, One, Zero};
+use crate::utils::modint::{Modulus, StaticModInt};
 use std::ops::*;
 
 // ------------ fp start ------------
@@ -276,11 +277,32 @@ forward_ref_binop! {
 
 // ------------ impl arith end ------------
 
+// ------------ impl conversion start ------------
+
+// 法が等しいときだけ変換できる
+impl<T: Mod, M: Modulus> From<StaticModInt<M>> for Fp<T> {
+    fn from(x: StaticModInt<M>) -> Self {
+        assert_eq!(T::MOD, M::MODULUS as i64, "moduli differ");
+        Self::unchecked(x.to_inner() as i64)
+    }
+}
+
+impl<T: Mod, M: Modulus> From<Fp<T>> for StaticModInt<M> {
+    fn from(x: Fp<T>) -> Self {
+        assert_eq!(T::MOD, M::MODULUS as i64, "moduli differ");
+        Self::new(x.into_inner() as u32)
+    }
+}
+
+// ------------ impl conversion end ------------
+
 // ------------ fp end ------------
 
 #[cfg(test)]
 mod tests {
-    use super::{Fp, Mod};
+    use super::{Fp, Mod, F1000000007, F998244353};
+    use crate::utils::algebraic_traits::Field;
+    use crate::utils::modint::{Mod1000000007, Mod998244353};
     define_fp!(F1009, Mod1009, 1009);
 
     #[test]
@@ -311,4 +333,36 @@ mod tests {
         let res = (1..Fp::r#mod()).map(Fp::new).product::<Fp>();
         assert_eq!(res, Fp::new(-1));
     }
+
+    fn field_pow<T: Field + Copy>(x: T, n: usize) -> T {
+        (0..n).fold(T::one(), |acc, _| acc * x)
+    }
+
+    #[test]
+    fn test_field() {
+        type Fp = F1009;
+        assert_eq!(field_pow(Fp::new(3), 5), Fp::new(243));
+        assert_eq!(field_pow(Fp::new(3), 1008), Fp::new(1));
+        assert_eq!(Fp::new(1) / Fp::new(3) * Fp::new(3), Fp::new(1));
+    }
+
+    #[test]
+    fn test_static_modint() {
+        for &x in &[0, 1, 2, 123_456_789, 998_244_352] {
+            let a = Mod998244353::new(x);
+            let b = F998244353::from(a);
+            assert_eq!(b.into_inner(), x as i64);
+            assert_eq!(Mod998244353::from(b), a);
+            assert_eq!(F998244353::from(a * a + a), b * b + b);
+            assert_eq!(F998244353::from(-a), -b);
+        }
+        let a = F1000000007::new(-1);
+        assert_eq!(Mod1000000007::from(a).to_inner(), 1_000_000_006);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_different_moduli() {
+        let _ = Mod998244353::from(F1000000007::new(1));
+    }
 }
@@ -0,0 +1,304 @@
+use crate::utils::algebraic_traits::{Associative, One, Zero};
+use crate::utils::math::gcd;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, RemAssign, Sub, SubAssign};
+
+// ------------ fraction start ------------
+
+/// 分母分子に使える符号付き整数 (i64, i128 など)
+pub trait Integer:
+    Copy
+    + Ord
+    + Zero
+    + One
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + RemAssign
+    + Neg<Output = Self>
+    + fmt::Display
+{
+}
+
+impl<T> Integer for T where
+    T: Copy
+        + Ord
+        + Zero
+        + One
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>
+        + RemAssign
+        + Neg<Output = T>
+        + fmt::Display
+{
+}
+
+/// 有理数
+/// 常に既約で分母は正 (0 は 0/1)
+/// 途中の値があふれると debug ビルドでは panic する
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fraction<T> {
+    num: T,
+    den: T,
+}
+
+fn abs<T: Integer>(x: T) -> T {
+    if x < T::zero() {
+        -x
+    } else {
+        x
+    }
+}
+
+impl<T: Integer> Fraction<T> {
+    /// num / den
+    pub fn new(num: T, den: T) -> Self {
+        assert!(!den.is_zero(), "denominator must not be zero");
+        let g = abs(gcd(num, den));
+        let (num, den) = (num / g, den / g);
+        if den < T::zero() {
+            Self::new_unchecked(-num, -den)
+        } else {
+            Self::new_unchecked(num, den)
+        }
+    }
+
+    fn new_unchecked(num: T, den: T) -> Self {
+        Self { num, den }
+    }
+
+    pub fn num(&self) -> T {
+        self.num
+    }
+
+    pub fn den(&self) -> T {
+        self.den
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::new_unchecked(abs(self.num), self.den)
+    }
+
+    pub fn recip(&self) -> Self {
+        assert!(!self.num.is_zero(), "attempt to divide by zero");
+        if self.num < T::zero() {
+            Self::new_unchecked(-self.den, -self.num)
+        } else {
+            Self::new_unchecked(self.den, self.num)
+        }
+    }
+
+    /// 以下の最大の整数
+    pub fn floor(&self) -> T {
+        let q = self.num / self.den;
+        if self.num < T::zero() && q * self.den != self.num {
+            q - T::one()
+        } else {
+            q
+        }
+    }
+
+    /// 以上の最小の整数
+    pub fn ceil(&self) -> T {
+        -(-*self).floor()
+    }
+}
+
+impl<T: Integer> From<T> for Fraction<T> {
+    fn from(x: T) -> Self {
+        Self::new_unchecked(x, T::one())
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: Integer> Add for Fraction<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let g = gcd(self.den, rhs.den);
+        let (a, b) = (self.den / g, rhs.den / g);
+        Self::new(self.num * b + rhs.num * a, a * rhs.den)
+    }
+}
+
+impl<T: Integer> Sub for Fraction<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: Integer> Mul for Fraction<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        // 先に約分してあふれにくくする
+        let g1 = abs(gcd(self.num, rhs.den));
+        let g2 = abs(gcd(rhs.num, self.den));
+        Self::new_unchecked(
+            (self.num / g1) * (rhs.num / g2),
+            (self.den / g2) * (rhs.den / g1),
+        )
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: Integer> Div for Fraction<T> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.recip()
+    }
+}
+
+impl<T: Integer> Neg for Fraction<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new_unchecked(-self.num, self.den)
+    }
+}
+
+impl<T: Integer> AddAssign for Fraction<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Integer> SubAssign for Fraction<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Integer> MulAssign for Fraction<T> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Integer> DivAssign for Fraction<T> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T: Integer> Ord for Fraction<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.num * other.den).cmp(&(other.num * self.den))
+    }
+}
+
+impl<T: Integer> PartialOrd for Fraction<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Integer> Associative for Fraction<T> {}
+
+impl<T: Integer> Zero for Fraction<T> {
+    fn zero() -> Self {
+        Self::new_unchecked(T::zero(), T::one())
+    }
+    fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+}
+
+impl<T: Integer> One for Fraction<T> {
+    fn one() -> Self {
+        Self::new_unchecked(T::one(), T::one())
+    }
+}
+
+impl<T: Integer> fmt::Display for Fraction<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.den.is_one() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl<T: Integer> fmt::Debug for Fraction<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+// ------------ fraction end ------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::algebraic_traits::Field;
+    use crate::utils::random::XorShift;
+    use rand::{Rng, SeedableRng};
+
+    type Q = Fraction<i64>;
+
+    fn q(num: i64, den: i64) -> Q {
+        Q::new(num, den)
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(q(2, 4), q(1, 2));
+        assert_eq!(q(3, -6), q(-1, 2));
+        assert_eq!((q(-3, -6).num(), q(-3, -6).den()), (1, 2));
+        assert_eq!(q(0, -5), Q::zero());
+        assert_eq!(q(0, -5).den(), 1);
+        assert_eq!(format!("{}", q(6, -4)), "-3/2");
+        assert_eq!(format!("{:?}", q(6, 3)), "2");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(q(1, 2) + q(1, 3), q(5, 6));
+        assert_eq!(q(1, 2) - q(1, 3), q(1, 6));
+        assert_eq!(q(2, 3) * q(9, 4), q(3, 2));
+        assert_eq!(q(2, 3) / q(-4, 9), q(-3, 2));
+        assert_eq!(q(1, 6) + q(1, 3), q(1, 2));
+        assert_eq!(q(0, 1) * q(5, 7), Q::zero());
+        assert_eq!((q(7, 2).floor(), q(7, 2).ceil()), (3, 4));
+        assert_eq!((q(-7, 2).floor(), q(-7, 2).ceil()), (-4, -3));
+        assert_eq!((q(-4, 2).floor(), q(-4, 2).ceil()), (-2, -2));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let mut v = (0..200)
+            .map(|_| q(rng.gen_range(-100..100), rng.gen_range(1..100)))
+            .collect::<Vec<_>>();
+        v.sort();
+        for w in v.windows(2) {
+            assert!(w[0].num() as f64 / w[0].den() as f64 <= w[1].num() as f64 / w[1].den() as f64);
+            assert_eq!(w[0] == w[1], w[0].cmp(&w[1]) == Ordering::Equal);
+        }
+        assert!(q(-1, 2) < q(-1, 3));
+        assert!(q(1, 3) < q(1, 2));
+    }
+
+    fn harmonic<T: Field + Copy + From<i128>>(n: i128) -> T {
+        (1..=n).fold(T::zero(), |acc, i| acc + T::one() / T::from(i))
+    }
+
+    #[test]
+    fn test_field_i128() {
+        // 調和数 H_30
+        let h = harmonic::<Fraction<i128>>(30);
+        assert_eq!(h, Fraction::new(9_304_682_830_147, 2_329_089_562_800));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_overflow() {
+        let x = q(1, 1 << 40);
+        let _ = x * x;
+    }
+}
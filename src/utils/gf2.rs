@@ -0,0 +1,421 @@
+use crate::utils::bitset::BitSet;
+use std::ops::{Index, IndexMut};
+
+// ------------ gf2 start ------------
+
+/// xor 基底
+/// 各基底の最上位ビットは他の基底に現れない (簡約済み)
+#[derive(Clone)]
+pub struct XorBasis {
+    width: usize,
+    // 最上位ビットの昇順
+    basis: Vec<(usize, BitSet)>,
+}
+
+impl XorBasis {
+    /// width ビットのベクトルを扱う
+    pub fn new(width: usize) -> Self {
+        Self {
+            width,
+            basis: Vec::new(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn rank(&self) -> usize {
+        self.basis.len()
+    }
+
+    pub fn basis(&self) -> impl Iterator<Item = &BitSet> {
+        self.basis.iter().map(|(_, b)| b)
+    }
+
+    fn reduce(&self, mut v: BitSet) -> BitSet {
+        for (l, b) in &self.basis {
+            if v.access(*l) {
+                v ^= b;
+            }
+        }
+        v
+    }
+
+    /// 張る空間が広がったら true
+    pub fn insert(&mut self, v: &BitSet) -> bool {
+        let v = self.reduce(self.widen(v));
+        let l = match v.highest_one() {
+            Some(l) => l,
+            None => return false,
+        };
+        for (_, b) in self.basis.iter_mut() {
+            if b.access(l) {
+                *b ^= &v;
+            }
+        }
+        let i = self
+            .basis
+            .binary_search_by_key(&l, |&(m, _)| m)
+            .unwrap_err();
+        self.basis.insert(i, (l, v));
+        true
+    }
+
+    pub fn contains(&self, v: &BitSet) -> bool {
+        !self.reduce(self.widen(v)).any()
+    }
+
+    /// x ^ (張る空間の元) の最大値
+    pub fn max_xor(&self, x: &BitSet) -> BitSet {
+        let mut x = self.widen(x);
+        for (l, b) in self.basis.iter().rev() {
+            if !x.access(*l) {
+                x ^= b;
+            }
+        }
+        x
+    }
+
+    /// x ^ (張る空間の元) の最小値
+    pub fn min_xor(&self, x: &BitSet) -> BitSet {
+        self.reduce(self.widen(x))
+    }
+
+    /// 張る空間の元のうち k 番目 (0-indexed) に小さいもの
+    pub fn kth(&self, k: u64) -> Option<BitSet> {
+        if self.rank() < 64 && k >> self.rank() != 0 {
+            return None;
+        }
+        let mut res = BitSet::new(self.width);
+        for (i, (_, b)) in self.basis.iter().take(64).enumerate() {
+            if k >> i & 1 == 1 {
+                res ^= b;
+            }
+        }
+        Some(res)
+    }
+
+    /// 二つの空間の共通部分
+    pub fn intersection(&self, other: &Self) -> Self {
+        assert_eq!(self.width, other.width, "width mismatch");
+        // (x, y) の組を x について掃き出す。y は常に self の空間の元で、x - y は other の空間の元
+        let mut pairs: Vec<Option<(BitSet, BitSet)>> = vec![None; self.width];
+        for (l, b) in &self.basis {
+            pairs[*l] = Some((b.clone(), b.clone()));
+        }
+        let mut res = Self::new(self.width);
+        for b in other.basis() {
+            let mut x = b.clone();
+            let mut y = BitSet::new(self.width);
+            while let Some(l) = x.highest_one() {
+                match &pairs[l] {
+                    Some((px, py)) => {
+                        x ^= px;
+                        y ^= py;
+                    }
+                    None => break,
+                }
+            }
+            match x.highest_one() {
+                Some(l) => pairs[l] = Some((x, y)),
+                None => {
+                    res.insert(&y);
+                }
+            }
+        }
+        res
+    }
+
+    fn widen(&self, v: &BitSet) -> BitSet {
+        let mut res = BitSet::new(self.width);
+        res ^= v;
+        res
+    }
+}
+
+/// GF(2) 上の行列
+#[derive(Clone)]
+pub struct BitMatrix {
+    h: usize,
+    w: usize,
+    rows: Vec<BitSet>,
+}
+
+/// 連立一次方程式の解。x は解の一つで、kernel は解空間の差分の基底
+#[derive(Clone)]
+pub struct BitSolution {
+    pub x: BitSet,
+    pub kernel: Vec<BitSet>,
+}
+
+impl BitMatrix {
+    /// h x w の零行列
+    pub fn new(h: usize, w: usize) -> Self {
+        Self {
+            h,
+            w,
+            rows: vec![BitSet::new(w); h],
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.h
+    }
+
+    pub fn width(&self) -> usize {
+        self.w
+    }
+
+    /// 列ベクトル v に左から掛ける
+    pub fn apply(&self, v: &BitSet) -> BitSet {
+        let mut res = BitSet::new(self.h);
+        for (i, r) in self.rows.iter().enumerate() {
+            let mut t = r.clone();
+            t &= v;
+            res.set(i, t.count_ones() & 1 == 1);
+        }
+        res
+    }
+
+    /// 左から cols 列について簡約行階段形にし、枢軸のある列を返す
+    fn eliminate(&mut self, cols: usize) -> Vec<usize> {
+        let mut pivots = vec![];
+        for c in 0..cols {
+            let r = pivots.len();
+            if r == self.h {
+                break;
+            }
+            let p = match (r..self.h).find(|&i| self.rows[i].access(c)) {
+                Some(p) => p,
+                None => continue,
+            };
+            self.rows.swap(p, r);
+            let pivot = self.rows[r].clone();
+            for (i, row) in self.rows.iter_mut().enumerate() {
+                if i != r && row.access(c) {
+                    *row ^= &pivot;
+                }
+            }
+            pivots.push(c);
+        }
+        pivots
+    }
+
+    /// 簡約行階段形と、枢軸のある列
+    pub fn rref(&self) -> (Self, Vec<usize>) {
+        let mut m = self.clone();
+        let pivots = m.eliminate(self.w);
+        (m, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rref().1.len()
+    }
+
+    /// Ax = 0 の解空間の基底
+    pub fn kernel(&self) -> Vec<BitSet> {
+        self.solve(&BitSet::new(self.h)).unwrap().kernel
+    }
+
+    /// Ax = b の解。解がなければ None
+    pub fn solve(&self, b: &BitSet) -> Option<BitSolution> {
+        let (h, w) = (self.h, self.w);
+        let mut m = Self::new(h, w + 1);
+        for i in 0..h {
+            m[i] ^= &self[i];
+            m[i].set(w, b.access(i));
+        }
+        let pivots = m.eliminate(w);
+        if (pivots.len()..h).any(|i| m[i].access(w)) {
+            return None;
+        }
+        let mut x = BitSet::new(w);
+        let mut is_pivot = vec![false; w];
+        for (i, &c) in pivots.iter().enumerate() {
+            x.set(c, m[i].access(w));
+            is_pivot[c] = true;
+        }
+        let kernel = (0..w)
+            .filter(|&f| !is_pivot[f])
+            .map(|f| {
+                let mut v = BitSet::new(w);
+                v.set(f, true);
+                for (i, &c) in pivots.iter().enumerate() {
+                    v.set(c, m[i].access(f));
+                }
+                v
+            })
+            .collect();
+        Some(BitSolution { x, kernel })
+    }
+}
+
+impl From<Vec<BitSet>> for BitMatrix {
+    /// 各行の幅は最初の行に揃える
+    fn from(rows: Vec<BitSet>) -> Self {
+        let h = rows.len();
+        let w = rows.first().map_or(0, |r| r.size());
+        let mut res = Self::new(h, w);
+        for (r, v) in res.rows.iter_mut().zip(&rows) {
+            *r ^= v;
+        }
+        res
+    }
+}
+
+impl Index<usize> for BitMatrix {
+    type Output = BitSet;
+    fn index(&self, i: usize) -> &BitSet {
+        &self.rows[i]
+    }
+}
+
+impl IndexMut<usize> for BitMatrix {
+    fn index_mut(&mut self, i: usize) -> &mut BitSet {
+        &mut self.rows[i]
+    }
+}
+
+// ------------ gf2 end ------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::random::XorShift;
+    use rand::{Rng, RngCore, SeedableRng};
+
+    fn from_u64(x: u64, w: usize) -> BitSet {
+        let mut res = BitSet::new(w);
+        for i in 0..w {
+            res.set(i, x >> i & 1 == 1);
+        }
+        res
+    }
+
+    fn to_u64(b: &BitSet) -> u64 {
+        b.collect().iter().fold(0, |acc, &i| acc | 1 << i)
+    }
+
+    fn span(v: &[u64]) -> Vec<u64> {
+        let mut res = vec![0];
+        for &x in v {
+            let t = res.iter().map(|&y| x ^ y).collect::<Vec<_>>();
+            res.extend(t);
+        }
+        res.sort();
+        res.dedup();
+        res
+    }
+
+    #[test]
+    fn test_xor_basis() {
+        let mut rng = XorShift::seed_from_u64(1);
+        let w = 10;
+        for _ in 0..50 {
+            let n = rng.gen_range(0..7);
+            let v = (0..n)
+                .map(|_| (rng.next_u64() % (1 << w)) & rng.next_u64())
+                .collect::<Vec<_>>();
+            let mut basis = XorBasis::new(w);
+            for &x in &v {
+                basis.insert(&from_u64(x, w));
+            }
+            let s = span(&v);
+            assert_eq!(1 << basis.rank(), s.len());
+            for (k, &x) in s.iter().enumerate() {
+                assert_eq!(to_u64(&basis.kth(k as u64).unwrap()), x);
+            }
+            assert!(basis.kth(s.len() as u64).is_none());
+            for x in 0..1 << w {
+                let b = from_u64(x, w);
+                assert_eq!(basis.contains(&b), s.binary_search(&x).is_ok());
+                let ys = s.iter().map(|y| x ^ y);
+                assert_eq!(to_u64(&basis.max_xor(&b)), ys.clone().max().unwrap());
+                assert_eq!(to_u64(&basis.min_xor(&b)), ys.min().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_intersection() {
+        let mut rng = XorShift::seed_from_u64(2);
+        let w = 8;
+        for _ in 0..100 {
+            let random_basis = |rng: &mut XorShift| {
+                let mut basis = XorBasis::new(w);
+                let mut v = vec![];
+                for _ in 0..rng.gen_range(0..6) {
+                    let x = rng.next_u64() % (1 << w);
+                    basis.insert(&from_u64(x, w));
+                    v.push(x);
+                }
+                (basis, span(&v))
+            };
+            let (a, sa) = random_basis(&mut rng);
+            let (b, sb) = random_basis(&mut rng);
+            let c = a.intersection(&b);
+            let sc = span(&c.basis().map(to_u64).collect::<Vec<_>>());
+            let expected = sa
+                .into_iter()
+                .filter(|x| sb.binary_search(x).is_ok())
+                .collect::<Vec<_>>();
+            assert_eq!(sc, expected);
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let mut rng = XorShift::seed_from_u64(3);
+        for _ in 0..30 {
+            let h = rng.gen_range(1..100);
+            let w = rng.gen_range(1..100);
+            let mut a = BitMatrix::new(h, w);
+            // 行を使い回して階数を落とす
+            for i in 0..h {
+                if i > 0 && rng.gen_range(0..3) == 0 {
+                    let r = a[rng.gen_range(0..i)].clone();
+                    a[i] = r;
+                    continue;
+                }
+                for j in 0..w {
+                    a[i].set(j, rng.next_u32() & 1 == 1);
+                }
+            }
+            let mut x0 = BitSet::new(w);
+            (0..w).for_each(|j| x0.set(j, rng.next_u32() & 1 == 1));
+            let b = a.apply(&x0);
+            let sol = a.solve(&b).unwrap();
+            assert_eq!(a.apply(&sol.x).collect(), b.collect());
+            assert_eq!(sol.kernel.len(), w - a.rank());
+            for k in &sol.kernel {
+                assert!(!a.apply(k).any());
+            }
+        }
+        // x0 + x1 = 1, x0 + x1 = 0 は解なし
+        let a = BitMatrix::from(vec![from_u64(0b11, 2), from_u64(0b11, 2)]);
+        assert!(a.solve(&from_u64(0b01, 2)).is_none());
+        assert_eq!(
+            a.kernel().iter().map(to_u64).collect::<Vec<_>>(),
+            vec![0b11]
+        );
+    }
+
+    #[test]
+    fn test_large() {
+        // 単位行列の上三角を埋めたもの
+        let n = 1000;
+        let mut a = BitMatrix::new(n, n);
+        for i in 0..n {
+            for j in i..n {
+                a[i].set(j, true);
+            }
+        }
+        assert_eq!(a.rank(), n);
+        let mut b = BitSet::new(n);
+        b.set(1, true);
+        let sol = a.solve(&b).unwrap();
+        assert_eq!(sol.x.collect(), vec![0, 1]);
+        assert!(sol.kernel.is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::utils::modint::{Modulus, StaticModInt};
+use crate::utils::polynomial::{ArrayNTT, NTTFriendly};
+
+// ------------ linear recurrence start ------------
+
+/// a の先頭から最短の線形漸化式 c を求める (Berlekamp-Massey)
+/// c.len() = d として a_i = c_0 * a_{i-d} + c_1 * a_{i-d+1} + ... + c_{d-1} * a_{i-1}
+/// (algorithms::kitamasa と同じ並び)
+/// 正しく復元するには a.len() >= 2d が必要
+/// O(N^2)
+pub fn berlekamp_massey<M: Modulus>(a: &[StaticModInt<M>]) -> Vec<StaticModInt<M>> {
+    let zero = StaticModInt::zero();
+    // c(x) = 1 + c_1 x + ... + c_l x^l で、a * c(x) の l 次以上が 0 になる
+    let mut c = vec![StaticModInt::one()];
+    let mut b = vec![StaticModInt::one()];
+    let (mut l, mut m) = (0, 1);
+    let mut bd = StaticModInt::one();
+    for i in 0..a.len() {
+        let d = (1..=l).fold(a[i], |acc, j| acc + c[j] * a[i - j]);
+        if d == zero {
+            m += 1;
+            continue;
+        }
+        let coef = d / bd;
+        let t = if 2 * l <= i { Some(c.clone()) } else { None };
+        if c.len() < b.len() + m {
+            c.resize(b.len() + m, zero);
+        }
+        for (c, &b) in c[m..].iter_mut().zip(&b) {
+            *c -= coef * b;
+        }
+        match t {
+            Some(t) => {
+                l = i + 1 - l;
+                b = t;
+                bd = d;
+                m = 1;
+            }
+            None => m += 1,
+        }
+    }
+    c.resize(l + 1, zero);
+    c[1..].iter().rev().map(|&x| -x).collect()
+}
+
+/// [x^n] p(x) / q(x) (Bostan-Mori)
+/// q(0) != 0
+/// O(K logK logN)
+pub fn bostan_mori<M: NTTFriendly>(
+    p: &[StaticModInt<M>],
+    q: &[StaticModInt<M>],
+    mut n: u64,
+) -> StaticModInt<M> {
+    assert!(
+        q.first().map_or(false, |x| x.to_inner() != 0),
+        "constant term of the denominator must not be zero"
+    );
+    let mut p = p.to_vec();
+    let mut q = q.to_vec();
+    while n > 0 && !p.is_empty() {
+        let mut qm = q.clone();
+        qm.iter_mut().skip(1).step_by(2).for_each(|x| *x = -*x);
+        let u = p.multiply(&qm);
+        let v = q.multiply(&qm);
+        p = u.into_iter().skip((n & 1) as usize).step_by(2).collect();
+        q = v.into_iter().step_by(2).collect();
+        n >>= 1;
+    }
+    match p.first() {
+        Some(&x) if n == 0 => x / q[0],
+        _ => StaticModInt::zero(),
+    }
+}
+
+/// a_i = a[i] (0 <= i < k),
+/// a_i = c_0 * a_{i-k} + c_1 * a_{i-k+1} + ... + c_{k-1} * a_{i-1}
+/// のとき a_n を求める
+/// O(K logK logN)
+pub fn nth_term<M: NTTFriendly>(
+    a: &[StaticModInt<M>],
+    c: &[StaticModInt<M>],
+    n: u64,
+) -> StaticModInt<M> {
+    assert_eq!(a.len(), c.len());
+    let k = a.len();
+    if n < k as u64 {
+        return a[n as usize];
+    }
+    // q(x) = 1 - c_{k-1} x - ... - c_0 x^k, p(x) = a(x) q(x) mod x^k
+    let mut q = vec![StaticModInt::one()];
+    q.extend(c.iter().rev().map(|&x| -x));
+    let mut p = a.multiply(&q);
+    p.truncate(k);
+    bostan_mori(&p, &q, n)
+}
+
+/// 数列の先頭から漸化式を推定して a_n を求める
+pub fn guess_nth_term<M: NTTFriendly>(a: &[StaticModInt<M>], n: u64) -> StaticModInt<M> {
+    if n < a.len() as u64 {
+        return a[n as usize];
+    }
+    let c = berlekamp_massey(a);
+    nth_term(&a[..c.len()], &c, n)
+}
+
+// ------------ linear recurrence end ------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::modint::Mod998244353;
+    use crate::utils::random::XorShift;
+    use rand::{Rng, RngCore, SeedableRng};
+
+    type Mint = Mod998244353;
+
+    fn mints(v: &[u32]) -> Vec<Mint> {
+        v.iter().map(|&x| Mint::new(x)).collect()
+    }
+
+    fn brute(a: &[Mint], c: &[Mint], n: usize) -> Mint {
+        let k = a.len();
+        let mut a = a.to_vec();
+        while a.len() <= n {
+            let t = a[a.len() - k..]
+                .iter()
+                .zip(c)
+                .fold(Mint::zero(), |acc, (&x, &y)| acc + x * y);
+            a.push(t);
+        }
+        a[n]
+    }
+
+    #[test]
+    fn test_berlekamp_massey() {
+        let fib = mints(&[0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+        assert_eq!(berlekamp_massey(&fib), mints(&[1, 1]));
+        assert_eq!(berlekamp_massey(&mints(&[0, 0, 0, 0])), vec![]);
+        assert_eq!(berlekamp_massey(&mints(&[2, 6, 18, 54])), mints(&[3]));
+        assert_eq!(berlekamp_massey(&mints(&[1, 2, 4, 8, 13])).len(), 4);
+
+        let mut rng = XorShift::seed_from_u64(1);
+        for _ in 0..50 {
+            let k = rng.gen_range(1..20);
+            let a = (0..k)
+                .map(|_| Mint::new(rng.next_u32()))
+                .collect::<Vec<_>>();
+            let c = (0..k)
+                .map(|_| Mint::new(rng.next_u32()))
+                .collect::<Vec<_>>();
+            let seq = (0..2 * k + 5).map(|i| brute(&a, &c, i)).collect::<Vec<_>>();
+            let d = berlekamp_massey(&seq);
+            assert!(d.len() <= k);
+            for i in d.len()..seq.len() {
+                let t = seq[i - d.len()..i]
+                    .iter()
+                    .zip(&d)
+                    .fold(Mint::zero(), |acc, (&x, &y)| acc + x * y);
+                assert_eq!(seq[i], t);
+            }
+        }
+    }
+
+    #[test]
+    fn test_nth_term() {
+        let a = mints(&[0, 1]);
+        let c = mints(&[1, 1]);
+        // F_100 mod 998244353
+        assert_eq!(nth_term(&a, &c, 100), brute(&a, &c, 100));
+        assert_eq!(nth_term(&a, &c, 0), Mint::new(0));
+        assert_eq!(nth_term(&a, &c, 1), Mint::new(1));
+
+        let mut rng = XorShift::seed_from_u64(2);
+        for _ in 0..20 {
+            let k = rng.gen_range(1..80);
+            let a = (0..k)
+                .map(|_| Mint::new(rng.next_u32()))
+                .collect::<Vec<_>>();
+            let c = (0..k)
+                .map(|_| Mint::new(rng.next_u32()))
+                .collect::<Vec<_>>();
+            let n = rng.gen_range(0..3000);
+            assert_eq!(nth_term(&a, &c, n as u64), brute(&a, &c, n));
+            let seq = (0..2 * k).map(|i| brute(&a, &c, i)).collect::<Vec<_>>();
+            assert_eq!(guess_nth_term(&seq, n as u64), brute(&a, &c, n));
+        }
+        // 2^n
+        let big = 1_000_000_000_000_000_000;
+        let expected = Mint::new(2).pow((big % 998_244_352) as u32);
+        assert_eq!(nth_term(&mints(&[1]), &mints(&[2]), big), expected);
+    }
+
+    #[test]
+    fn test_bostan_mori() {
+        // 1 / (1 - x)^2 = sum (n + 1) x^n
+        let q = mints(&[1, 998_244_351, 1]);
+        assert_eq!(bostan_mori(&mints(&[1]), &q, 12345), Mint::new(12346));
+        // 分子の次数が分母以上でもよい
+        assert_eq!(
+            bostan_mori(&mints(&[0, 0, 0, 5]), &mints(&[1]), 3),
+            Mint::new(5)
+        );
+        assert_eq!(
+            bostan_mori(&mints(&[0, 0, 0, 5]), &mints(&[1]), 4),
+            Mint::new(0)
+        );
+    }
+}
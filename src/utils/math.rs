@@ -1,5 +1,8 @@
-pub fn gcd(mut a: i64, mut b: i64) -> i64 {
-    while b != 0 {
+use crate::utils::algebraic_traits::Zero;
+use std::ops::RemAssign;
+
+pub fn gcd<T: Copy + Zero + RemAssign>(mut a: T, mut b: T) -> T {
+    while !b.is_zero() {
         a %= b;
         std::mem::swap(&mut a, &mut b);
     }
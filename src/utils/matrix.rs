@@ -0,0 +1,553 @@
+use crate::utils::algebraic_traits::{Associative, Field, One, Ring, SemiRing, Zero};
+use crate::utils::fp::{Fp, Mod};
+use crate::utils::fraction::{Fraction, Integer};
+use crate::utils::modint::{DynamicModInt, Modulus, StaticModInt};
+use crate::utils::monoids::Bounded;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub};
+
+// ------------ matrix start ------------
+
+/// 半環上の行列
+#[derive(Clone, PartialEq, Debug)]
+pub struct Matrix<T> {
+    h: usize,
+    w: usize,
+    a: Vec<T>,
+}
+
+impl<T: SemiRing> Matrix<T> {
+    /// h x w の零行列
+    pub fn new(h: usize, w: usize) -> Self {
+        Self {
+            h,
+            w,
+            a: vec![T::zero(); h * w],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut res = Self::new(n, n);
+        for i in 0..n {
+            res[i][i] = T::one();
+        }
+        res
+    }
+
+    pub fn height(&self) -> usize {
+        self.h
+    }
+
+    pub fn width(&self) -> usize {
+        self.w
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut res = Self::new(self.w, self.h);
+        for i in 0..self.h {
+            for j in 0..self.w {
+                res[j][i] = self[i][j].clone();
+            }
+        }
+        res
+    }
+
+    pub fn pow(&self, mut e: u64) -> Self {
+        assert_eq!(self.h, self.w, "matrix must be square");
+        let mut x = self.clone();
+        let mut y = Self::identity(self.h);
+        while e > 0 {
+            if e & 1 == 1 {
+                y = &y * &x;
+            }
+            e >>= 1;
+            if e > 0 {
+                x = &x * &x;
+            }
+        }
+        y
+    }
+
+    /// 列ベクトル v に左から掛ける
+    pub fn apply(&self, v: &[T]) -> Vec<T> {
+        assert_eq!(self.w, v.len(), "dimension mismatch");
+        (0..self.h)
+            .map(|i| {
+                self[i]
+                    .iter()
+                    .zip(v)
+                    .fold(T::zero(), |acc, (a, b)| acc + a.clone() * b.clone())
+            })
+            .collect()
+    }
+}
+
+impl<T: Clone> From<Vec<Vec<T>>> for Matrix<T> {
+    fn from(v: Vec<Vec<T>>) -> Self {
+        let h = v.len();
+        let w = v.first().map_or(0, |r| r.len());
+        assert!(
+            v.iter().all(|r| r.len() == w),
+            "rows must have the same length"
+        );
+        Self {
+            h,
+            w,
+            a: v.into_iter().flatten().collect(),
+        }
+    }
+}
+
+impl<T> Index<usize> for Matrix<T> {
+    type Output = [T];
+    fn index(&self, i: usize) -> &[T] {
+        &self.a[i * self.w..(i + 1) * self.w]
+    }
+}
+
+impl<T> IndexMut<usize> for Matrix<T> {
+    fn index_mut(&mut self, i: usize) -> &mut [T] {
+        &mut self.a[i * self.w..(i + 1) * self.w]
+    }
+}
+
+impl<T: SemiRing> Add for &Matrix<T> {
+    type Output = Matrix<T>;
+    fn add(self, rhs: Self) -> Matrix<T> {
+        assert_eq!((self.h, self.w), (rhs.h, rhs.w), "dimension mismatch");
+        Matrix {
+            h: self.h,
+            w: self.w,
+            a: self
+                .a
+                .iter()
+                .zip(&rhs.a)
+                .map(|(x, y)| x.clone() + y.clone())
+                .collect(),
+        }
+    }
+}
+
+impl<T: Ring> Sub for &Matrix<T> {
+    type Output = Matrix<T>;
+    fn sub(self, rhs: Self) -> Matrix<T> {
+        self + &-rhs
+    }
+}
+
+impl<T: SemiRing> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+    fn mul(self, rhs: Self) -> Matrix<T> {
+        assert_eq!(self.w, rhs.h, "dimension mismatch");
+        let mut res = Matrix::new(self.h, rhs.w);
+        for i in 0..self.h {
+            for k in 0..self.w {
+                let x = &self[i][k];
+                for (c, y) in res[i].iter_mut().zip(&rhs[k]) {
+                    *c += x.clone() * y.clone();
+                }
+            }
+        }
+        res
+    }
+}
+
+impl<T: Ring> Neg for &Matrix<T> {
+    type Output = Matrix<T>;
+    fn neg(self) -> Matrix<T> {
+        Matrix {
+            h: self.h,
+            w: self.w,
+            a: self.a.iter().map(|x| -x.clone()).collect(),
+        }
+    }
+}
+
+macro_rules! forward_matrix_op {
+    ($(impl $imp:ident, $method:ident, $bound:ident;)*) => {
+        $(
+            impl<T: $bound> $imp for Matrix<T> {
+                type Output = Self;
+                fn $method(self, rhs: Self) -> Self {
+                    $imp::$method(&self, &rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_matrix_op! {
+    impl Add, add, SemiRing;
+    impl Sub, sub, Ring;
+    impl Mul, mul, SemiRing;
+}
+
+impl<T: Ring> Neg for Matrix<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        -&self
+    }
+}
+
+impl<T: SemiRing> AddAssign for Matrix<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = &*self + &rhs;
+    }
+}
+
+impl<T: SemiRing> MulAssign for Matrix<T> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = &*self * &rhs;
+    }
+}
+
+/// 掃き出し法で使う体
+/// 枢軸には prefer で最も良いものを選ぶ (浮動小数点数では絶対値最大の部分ピボット選択)
+pub trait Pivot: Field + Copy {
+    /// 0 とみなすか
+    fn is_negligible(&self) -> bool {
+        self.is_zero()
+    }
+    /// 枢軸として self より other の方が良いか
+    fn prefer(&self, other: &Self) -> bool {
+        self.is_negligible() && !other.is_negligible()
+    }
+}
+
+impl<M: Modulus> Pivot for StaticModInt<M> {}
+impl Pivot for DynamicModInt {}
+impl<T: Mod> Pivot for Fp<T> {}
+impl<T: Integer> Pivot for Fraction<T> {}
+
+impl Pivot for f64 {
+    fn is_negligible(&self) -> bool {
+        self.abs() < 1e-9
+    }
+    fn prefer(&self, other: &Self) -> bool {
+        self.abs() < other.abs()
+    }
+}
+
+/// 連立一次方程式の解。x は解の一つで、kernel は解空間の差分の基底
+#[derive(Clone, PartialEq, Debug)]
+pub struct Solution<T> {
+    pub x: Vec<T>,
+    pub kernel: Vec<Vec<T>>,
+}
+
+impl<T: Pivot> Matrix<T> {
+    /// 左から cols 列について簡約行階段形にする
+    /// 枢軸のある列と、行の入れ替えと正規化で行列式に掛かった値を返す
+    fn eliminate(&mut self, cols: usize) -> (Vec<usize>, T) {
+        let mut pivots = vec![];
+        let mut det = T::one();
+        for c in 0..cols {
+            let r = pivots.len();
+            if r == self.h {
+                break;
+            }
+            let mut p = r;
+            for i in r + 1..self.h {
+                if self[p][c].prefer(&self[i][c]) {
+                    p = i;
+                }
+            }
+            if self[p][c].is_negligible() {
+                continue;
+            }
+            if p != r {
+                for j in 0..self.w {
+                    self.a.swap(p * self.w + j, r * self.w + j);
+                }
+                det = -det;
+            }
+            let v = self[r][c];
+            det *= v;
+            let inv = T::one() / v;
+            self[r].iter_mut().for_each(|x| *x *= inv);
+            for i in 0..self.h {
+                if i == r || self[i][c].is_zero() {
+                    continue;
+                }
+                let f = self[i][c];
+                for j in 0..self.w {
+                    let t = self[r][j] * f;
+                    self[i][j] += -t;
+                }
+            }
+            pivots.push(c);
+        }
+        (pivots, det)
+    }
+
+    /// 簡約行階段形と、枢軸のある列
+    pub fn rref(&self) -> (Self, Vec<usize>) {
+        let mut m = self.clone();
+        let (pivots, _) = m.eliminate(self.w);
+        (m, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rref().1.len()
+    }
+
+    pub fn determinant(&self) -> T {
+        assert_eq!(self.h, self.w, "matrix must be square");
+        let mut m = self.clone();
+        let (pivots, det) = m.eliminate(self.w);
+        if pivots.len() == self.h {
+            det
+        } else {
+            T::zero()
+        }
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        assert_eq!(self.h, self.w, "matrix must be square");
+        let n = self.h;
+        let mut m = Self::new(n, 2 * n);
+        for i in 0..n {
+            m[i][..n].copy_from_slice(&self[i]);
+            m[i][n + i] = T::one();
+        }
+        let (pivots, _) = m.eliminate(n);
+        if pivots.len() < n {
+            return None;
+        }
+        let mut res = Self::new(n, n);
+        for i in 0..n {
+            res[i].copy_from_slice(&m[i][n..]);
+        }
+        Some(res)
+    }
+
+    /// Ax = 0 の解空間の基底
+    pub fn kernel(&self) -> Vec<Vec<T>> {
+        self.solve(&vec![T::zero(); self.h]).unwrap().kernel
+    }
+
+    /// Ax = b の解。解がなければ None
+    pub fn solve(&self, b: &[T]) -> Option<Solution<T>> {
+        assert_eq!(self.h, b.len(), "dimension mismatch");
+        let (h, w) = (self.h, self.w);
+        let mut m = Self::new(h, w + 1);
+        for i in 0..h {
+            m[i][..w].copy_from_slice(&self[i]);
+            m[i][w] = b[i];
+        }
+        let (pivots, _) = m.eliminate(w);
+        if (pivots.len()..h).any(|i| !m[i][w].is_negligible()) {
+            return None;
+        }
+        let mut x = vec![T::zero(); w];
+        for (i, &c) in pivots.iter().enumerate() {
+            x[c] = m[i][w];
+        }
+        let mut is_pivot = vec![false; w];
+        pivots.iter().for_each(|&c| is_pivot[c] = true);
+        let kernel = (0..w)
+            .filter(|&f| !is_pivot[f])
+            .map(|f| {
+                let mut v = vec![T::zero(); w];
+                v[f] = T::one();
+                for (i, &c) in pivots.iter().enumerate() {
+                    v[c] = -m[i][f];
+                }
+                v
+            })
+            .collect();
+        Some(Solution { x, kernel })
+    }
+}
+
+/// (min, +) 半環
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MinPlus<T>(pub T);
+
+/// (max, +) 半環
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MaxPlus<T>(pub T);
+
+macro_rules! impl_tropical {
+    ($name:ident, $choose:ident, $inf:ident) => {
+        impl<T: Copy + PartialOrd> Add for $name<T> {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                if (rhs.0).$choose(&self.0) {
+                    rhs
+                } else {
+                    self
+                }
+            }
+        }
+
+        impl<T: Copy + PartialOrd> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        /// 零元 (到達不能) は吸収する
+        #[allow(clippy::suspicious_arithmetic_impl)]
+        impl<T: Copy + PartialOrd + Bounded + Add<Output = T>> Mul for $name<T> {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                if self.0 == T::$inf() || rhs.0 == T::$inf() {
+                    $name(T::$inf())
+                } else {
+                    $name(self.0 + rhs.0)
+                }
+            }
+        }
+
+        impl<T: Copy + PartialOrd> Associative for $name<T> {}
+
+        impl<T: Copy + PartialOrd + Bounded> Zero for $name<T> {
+            fn zero() -> Self {
+                $name(T::$inf())
+            }
+        }
+
+        impl<T: Copy + PartialOrd + Bounded + Add<Output = T> + Zero> One for $name<T> {
+            fn one() -> Self {
+                $name(T::zero())
+            }
+        }
+    };
+}
+
+impl_tropical!(MinPlus, lt, max_value);
+impl_tropical!(MaxPlus, gt, min_value);
+
+// ------------ matrix end ------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::modint::Mod998244353;
+    use crate::utils::random::XorShift;
+    use rand::{Rng, RngCore, SeedableRng};
+
+    type Mint = Mod998244353;
+
+    fn random_matrix(rng: &mut XorShift, h: usize, w: usize, p: u32) -> Matrix<Mint> {
+        let mut m = Matrix::new(h, w);
+        for i in 0..h {
+            for j in 0..w {
+                m[i][j] = Mint::new(rng.next_u32() % p);
+            }
+        }
+        m
+    }
+
+    #[test]
+    fn test_semiring() {
+        let a = Matrix::from(vec![vec![1i64, 1], vec![1, 0]]);
+        assert_eq!(a.pow(10)[0][1], 55);
+        assert_eq!(a.pow(0), Matrix::identity(2));
+        assert_eq!(&a + &a, Matrix::from(vec![vec![2, 2], vec![2, 0]]));
+        assert_eq!(a.clone() - a.clone(), Matrix::new(2, 2));
+        assert_eq!(a.transpose().apply(&[1, 2]), vec![3, 1]);
+
+        // 辺がちょうど k 本の最短路と最長路
+        let inf = std::i64::MAX;
+        let d = [vec![inf, 1, 5], vec![inf, inf, 2], vec![1, inf, inf]];
+        let m = Matrix::from(
+            d.iter()
+                .map(|r| r.iter().map(|&x| MinPlus(x)).collect())
+                .collect::<Vec<Vec<_>>>(),
+        );
+        assert_eq!(m.pow(2)[0][2], MinPlus(3));
+        assert_eq!(m.pow(3)[0][0], MinPlus(4));
+        assert_eq!(m.pow(2)[1][1], MinPlus(inf));
+        let m = Matrix::from(vec![
+            vec![MaxPlus(0), MaxPlus(3)],
+            vec![MaxPlus(2), MaxPlus(std::i64::MIN)],
+        ]);
+        assert_eq!(m.pow(3)[0][0], MaxPlus(5));
+    }
+
+    #[test]
+    fn test_modint() {
+        let mut rng = XorShift::seed_from_u64(1);
+        for n in 1..8 {
+            let a = random_matrix(&mut rng, n, n, 998_244_353);
+            let inv = a.inverse().unwrap();
+            assert_eq!(&a * &inv, Matrix::identity(n));
+            assert_eq!(a.determinant() * inv.determinant(), Mint::new(1));
+            assert_eq!(a.rank(), n);
+        }
+        // 余因子展開と比べる
+        let a = Matrix::from(vec![
+            vec![Mint::new(2), Mint::new(0), Mint::new(1)],
+            vec![Mint::new(1), Mint::new(3), Mint::new(2)],
+            vec![Mint::new(1), Mint::new(1), Mint::new(2)],
+        ]);
+        assert_eq!(a.determinant(), Mint::new(6));
+        let singular = Matrix::from(vec![
+            vec![Mint::new(1), Mint::new(2)],
+            vec![Mint::new(2), Mint::new(4)],
+        ]);
+        assert_eq!(singular.determinant(), Mint::new(0));
+        assert!(singular.inverse().is_none());
+        assert_eq!(singular.rank(), 1);
+    }
+
+    #[test]
+    fn test_solve() {
+        let mut rng = XorShift::seed_from_u64(2);
+        for _ in 0..100 {
+            let (h, w) = (rng.gen_range(1..7), rng.gen_range(1..7));
+            // 成分を小さくして階数が落ちやすくする
+            let a = random_matrix(&mut rng, h, w, 3);
+            let x0 = random_matrix(&mut rng, w, 1, 3).transpose()[0].to_vec();
+            let b = a.apply(&x0);
+            let sol = a.solve(&b).unwrap();
+            assert_eq!(a.apply(&sol.x), b);
+            assert_eq!(sol.kernel.len(), w - a.rank());
+            for k in &sol.kernel {
+                assert!(a.apply(k).iter().all(|x| x.is_zero()));
+            }
+            assert_eq!(a.kernel(), sol.kernel);
+        }
+        let a = Matrix::from(vec![
+            vec![Mint::new(1), Mint::new(1)],
+            vec![Mint::new(2), Mint::new(2)],
+        ]);
+        assert!(a.solve(&[Mint::new(1), Mint::new(3)]).is_none());
+    }
+
+    #[test]
+    fn test_float() {
+        // ヒルベルト行列
+        let n = 5;
+        let a = Matrix::from(
+            (0..n)
+                .map(|i| (0..n).map(|j| 1. / (i + j + 1) as f64).collect())
+                .collect::<Vec<Vec<f64>>>(),
+        );
+        let prod = &a * &a.inverse().unwrap();
+        for i in 0..n {
+            for j in 0..n {
+                let e = if i == j { 1. } else { 0. };
+                assert!((prod[i][j] - e).abs() < 1e-6);
+            }
+        }
+        // 枢軸選択がないと 0 で割る
+        let a = Matrix::from(vec![vec![0., 1.], vec![1., 1.]]);
+        let sol = a.solve(&[2., 3.]).unwrap();
+        assert!((sol.x[0] - 1.).abs() < 1e-9 && (sol.x[1] - 2.).abs() < 1e-9);
+        assert!((a.determinant() + 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fraction() {
+        type Q = Fraction<i64>;
+        let a = Matrix::from(
+            (0..4)
+                .map(|i| (0..4).map(|j| Q::new(1, i + j + 1)).collect())
+                .collect::<Vec<Vec<Q>>>(),
+        );
+        assert_eq!(a.determinant(), Q::new(1, 6_048_000));
+        assert_eq!(a.inverse().unwrap()[0][0], Q::from(16));
+    }
+}
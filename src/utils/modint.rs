@@ -1,3 +1,6 @@
+use crate::utils::algebraic_traits::{Associative, One, Zero};
+use std::cell::Cell;
+use std::fmt;
 use std::marker::PhantomData;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
@@ -20,9 +23,16 @@ pub trait Modulus {
     const MODULUS: u32;
 }
 
-#[derive(Eq, PartialEq)]
 pub struct StaticModInt<M: Modulus>(u32, PhantomData<fn() -> M>);
 
+impl<M: Modulus> PartialEq for StaticModInt<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<M: Modulus> Eq for StaticModInt<M> {}
+
 impl<M: Modulus> Clone for StaticModInt<M> {
     fn clone(&self) -> Self {
         Self::new_unchecked(self.0)
@@ -159,6 +169,38 @@ impl<M: Modulus> Neg for StaticModInt<M> {
     }
 }
 
+impl<M: Modulus> fmt::Debug for StaticModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<M: Modulus> Associative for StaticModInt<M> {}
+
+impl<M: Modulus> Zero for StaticModInt<M> {
+    fn zero() -> Self {
+        Self::new_unchecked(0)
+    }
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<M: Modulus> One for StaticModInt<M> {
+    fn one() -> Self {
+        Self::new_unchecked(1)
+    }
+    fn is_one(&self) -> bool {
+        self.0 == 1
+    }
+}
+
+impl<M: Modulus> From<u32> for StaticModInt<M> {
+    fn from(val: u32) -> Self {
+        Self::new(val)
+    }
+}
+
 impl<M: Modulus> From<usize> for StaticModInt<M> {
     fn from(val: usize) -> Self {
         Self::new_unchecked((val % M::MODULUS as usize) as u32)
@@ -178,7 +220,522 @@ impl<M: Modulus> From<i64> for StaticModInt<M> {
     }
 }
 
+/// 実行時に法を決める modint
+/// 法はスレッドごとに一つで、set_modulus で変えると既存の値は意味を失う
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DynamicModInt(u32);
+
+/// Barrett reduction (m < 2^31)
+#[derive(Clone, Copy)]
+struct Barrett {
+    m: u32,
+    im: u64,
+}
+
+impl Barrett {
+    fn new(m: u32) -> Self {
+        Self {
+            m,
+            im: (std::u64::MAX / m as u64).wrapping_add(1),
+        }
+    }
+
+    /// a * b mod m (a, b < m)
+    #[inline]
+    fn mul(&self, a: u32, b: u32) -> u32 {
+        let z = a as u64 * b as u64;
+        let x = ((z as u128 * self.im as u128) >> 64) as u64;
+        let mut v = z.wrapping_sub(x.wrapping_mul(self.m as u64)) as u32;
+        if self.m <= v {
+            v = v.wrapping_add(self.m);
+        }
+        v
+    }
+}
+
+thread_local! {
+    static BARRETT: Cell<Barrett> = Cell::new(Barrett::new(998_244_353));
+}
+
+impl DynamicModInt {
+    /// 法を m (1 <= m < 2^31) にする (初期値は 998244353)
+    pub fn set_modulus(m: u32) {
+        assert!((1..1 << 31).contains(&m), "modulus out of range: {}", m);
+        BARRETT.with(|b| b.set(Barrett::new(m)));
+    }
+
+    #[inline]
+    pub fn modulus() -> u32 {
+        BARRETT.with(|b| b.get().m)
+    }
+
+    #[inline]
+    pub fn new(v: u32) -> Self {
+        Self(v % Self::modulus())
+    }
+
+    #[inline]
+    fn new_unchecked(v: u32) -> Self {
+        Self(v)
+    }
+
+    #[inline]
+    pub fn zero() -> Self {
+        Self::new_unchecked(0)
+    }
+
+    #[inline]
+    pub fn one() -> Self {
+        Self::new(1)
+    }
+
+    #[inline]
+    pub fn to_inner(self) -> u32 {
+        self.0
+    }
+
+    pub fn pow(self, mut n: u32) -> Self {
+        let mut x = self;
+        let mut y = Self::one();
+        while n > 0 {
+            if n & 1 == 1 {
+                y *= x;
+            }
+            x *= x;
+            n >>= 1;
+        }
+        y
+    }
+
+    /// 法が素数でなくてもよい。逆元がなければ panic
+    pub fn inv(self) -> Self {
+        let m = Self::modulus() as i64;
+        let mut a = self.0 as i64;
+        let mut b = m;
+        let mut u = 1;
+        let mut v = 0;
+        while b > 0 {
+            let t = a / b;
+            a -= t * b;
+            u -= t * v;
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut u, &mut v);
+        }
+        assert!(a == 1 || m == 1, "{} is not invertible mod {}", self.0, m);
+        Self::new_unchecked(u.rem_euclid(m) as u32)
+    }
+}
+
+impl Add for DynamicModInt {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let m = Self::modulus();
+        let mut sum = self.0 + rhs.0;
+        if sum >= m {
+            sum -= m;
+        }
+        Self::new_unchecked(sum)
+    }
+}
+
+impl AddAssign for DynamicModInt {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for DynamicModInt {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        let m = Self::modulus();
+        let mut res = self.0 + m - rhs.0;
+        if res >= m {
+            res -= m;
+        }
+        Self::new_unchecked(res)
+    }
+}
+
+impl SubAssign for DynamicModInt {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for DynamicModInt {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new_unchecked(BARRETT.with(|b| b.get().mul(self.0, rhs.0)))
+    }
+}
+
+impl MulAssign for DynamicModInt {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div for DynamicModInt {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl DivAssign for DynamicModInt {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Neg for DynamicModInt {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new_unchecked(if self.0 == 0 {
+            0
+        } else {
+            Self::modulus() - self.0
+        })
+    }
+}
+
+impl fmt::Debug for DynamicModInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Associative for DynamicModInt {}
+
+impl Zero for DynamicModInt {
+    fn zero() -> Self {
+        Self::new_unchecked(0)
+    }
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl One for DynamicModInt {
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+
+impl From<u32> for DynamicModInt {
+    fn from(val: u32) -> Self {
+        Self::new(val)
+    }
+}
+
+impl From<usize> for DynamicModInt {
+    fn from(val: usize) -> Self {
+        Self::new_unchecked((val % Self::modulus() as usize) as u32)
+    }
+}
+
+impl From<u64> for DynamicModInt {
+    fn from(val: u64) -> Self {
+        Self::new_unchecked((val % Self::modulus() as u64) as u32)
+    }
+}
+
+impl From<i64> for DynamicModInt {
+    fn from(val: i64) -> Self {
+        Self::new_unchecked(val.rem_euclid(Self::modulus() as i64) as u32)
+    }
+}
+
+/// 奇数の法 n (< 2^64) についての Montgomery 乗算
+/// 値は MontgomeryModInt として扱う
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery64 {
+    n: u64,
+    /// n * n_inv = 1 mod 2^64
+    n_inv: u64,
+    /// 2^128 mod n
+    r2: u64,
+}
+
+impl Montgomery64 {
+    pub fn new(n: u64) -> Self {
+        assert!(n & 1 == 1, "modulus must be odd: {}", n);
+        let mut n_inv = n;
+        for _ in 0..5 {
+            n_inv = n_inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+        let r = ((1u128 << 64) % n as u128) as u64;
+        let r2 = (r as u128 * r as u128 % n as u128) as u64;
+        Self { n, n_inv, r2 }
+    }
+
+    #[inline]
+    pub fn modulus(&self) -> u64 {
+        self.n
+    }
+
+    /// t / 2^64 mod n (t < n 2^64)
+    #[inline]
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.n_inv);
+        let mn = ((m as u128 * self.n as u128) >> 64) as u64;
+        let hi = (t >> 64) as u64;
+        if hi < mn {
+            hi.wrapping_sub(mn).wrapping_add(self.n)
+        } else {
+            hi - mn
+        }
+    }
+
+    #[inline]
+    pub fn element(&self, x: u64) -> MontgomeryModInt<'_> {
+        let x = if x < self.n { x } else { x % self.n };
+        MontgomeryModInt {
+            x: self.reduce(x as u128 * self.r2 as u128),
+            mont: self,
+        }
+    }
+
+    #[inline]
+    pub fn zero(&self) -> MontgomeryModInt<'_> {
+        MontgomeryModInt { x: 0, mont: self }
+    }
+
+    #[inline]
+    pub fn one(&self) -> MontgomeryModInt<'_> {
+        self.element(1)
+    }
+}
+
+/// Montgomery64 の法での値 (内部では x 2^64 mod n を持つ)
+#[derive(Clone, Copy)]
+pub struct MontgomeryModInt<'a> {
+    x: u64,
+    mont: &'a Montgomery64,
+}
+
+impl<'a> MontgomeryModInt<'a> {
+    #[inline]
+    pub fn to_inner(self) -> u64 {
+        self.mont.reduce(self.x as u128)
+    }
+
+    #[inline]
+    pub fn is_zero(self) -> bool {
+        self.x == 0
+    }
+
+    pub fn pow(self, mut n: u64) -> Self {
+        let mut x = self;
+        let mut y = self.mont.one();
+        while n > 0 {
+            if n & 1 == 1 {
+                y *= x;
+            }
+            x *= x;
+            n >>= 1;
+        }
+        y
+    }
+
+    /// 法が素数でなくてもよい。逆元がなければ panic
+    pub fn inv(self) -> Self {
+        let m = self.mont.n as i128;
+        let mut a = self.to_inner() as i128;
+        let mut b = m;
+        let mut u = 1;
+        let mut v = 0;
+        while b > 0 {
+            let t = a / b;
+            a -= t * b;
+            u -= t * v;
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut u, &mut v);
+        }
+        assert!(a == 1 || m == 1, "not invertible mod {}", m);
+        self.mont.element(u.rem_euclid(m) as u64)
+    }
+}
+
+impl<'a> PartialEq for MontgomeryModInt<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x
+    }
+}
+
+impl<'a> Eq for MontgomeryModInt<'a> {}
+
+impl<'a> Add for MontgomeryModInt<'a> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let n = self.mont.n;
+        let (sum, overflow) = self.x.overflowing_add(rhs.x);
+        let x = if overflow || sum >= n {
+            sum.wrapping_sub(n)
+        } else {
+            sum
+        };
+        Self { x, mont: self.mont }
+    }
+}
+
+impl<'a> AddAssign for MontgomeryModInt<'a> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<'a> Sub for MontgomeryModInt<'a> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        let x = if self.x >= rhs.x {
+            self.x - rhs.x
+        } else {
+            self.x.wrapping_sub(rhs.x).wrapping_add(self.mont.n)
+        };
+        Self { x, mont: self.mont }
+    }
+}
+
+impl<'a> SubAssign for MontgomeryModInt<'a> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<'a> Mul for MontgomeryModInt<'a> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            x: self.mont.reduce(self.x as u128 * rhs.x as u128),
+            mont: self.mont,
+        }
+    }
+}
+
+impl<'a> MulAssign for MontgomeryModInt<'a> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<'a> Div for MontgomeryModInt<'a> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl<'a> DivAssign for MontgomeryModInt<'a> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<'a> Neg for MontgomeryModInt<'a> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.mont.zero() - self
+    }
+}
+
+impl<'a> fmt::Debug for MontgomeryModInt<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_inner())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    // use super::*;
+    use super::*;
+    use crate::utils::random::XorShift;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_dynamic_matches_static() {
+        let mut rng = XorShift::seed_from_u64(1);
+        DynamicModInt::set_modulus(1_000_000_007);
+        for _ in 0..1000 {
+            let (a, b) = (
+                rng.gen_range(0..std::u32::MAX),
+                rng.gen_range(1..1_000_000_007),
+            );
+            let (x, y) = (DynamicModInt::new(a), DynamicModInt::new(b));
+            let (s, t) = (Mod1000000007::new(a), Mod1000000007::new(b));
+            assert_eq!((x + y).to_inner(), (s + t).to_inner());
+            assert_eq!((x - y).to_inner(), (s - t).to_inner());
+            assert_eq!((x * y).to_inner(), (s * t).to_inner());
+            assert_eq!((x / y).to_inner(), (s / t).to_inner());
+            assert_eq!((-x).to_inner(), (-s).to_inner());
+            assert_eq!(x.pow(b).to_inner(), s.pow(b).to_inner());
+        }
+    }
+
+    #[test]
+    fn test_barrett() {
+        let mut rng = XorShift::seed_from_u64(2);
+        for &m in &[1, 2, 3, 1_000_000_000, 2_147_483_647] {
+            let b = Barrett::new(m);
+            for _ in 0..1000 {
+                let (x, y) = (rng.gen_range(0..m), rng.gen_range(0..m));
+                assert_eq!(b.mul(x, y) as u64, x as u64 * y as u64 % m as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_composite_modulus() {
+        DynamicModInt::set_modulus(1_000_000_000);
+        let x = DynamicModInt::from(-3i64);
+        assert_eq!(x.to_inner(), 999_999_997);
+        assert_eq!((x * x.inv()).to_inner(), 1);
+        assert_eq!(
+            (DynamicModInt::new(7) / DynamicModInt::new(3)).to_inner() * 3 % 1_000_000_000,
+            7
+        );
+        DynamicModInt::set_modulus(1);
+        assert!(DynamicModInt::one().is_zero());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_not_invertible() {
+        DynamicModInt::set_modulus(12);
+        DynamicModInt::new(4).inv();
+    }
+
+    #[test]
+    fn test_montgomery() {
+        let mut rng = XorShift::seed_from_u64(3);
+        let moduli = [
+            1,
+            3,
+            998_244_353,
+            (1 << 61) - 1,
+            (1 << 63) + 29,
+            std::u64::MAX,
+        ];
+        for &n in &moduli {
+            let mont = Montgomery64::new(n);
+            for _ in 0..1000 {
+                let (a, b) = (rng.gen(), rng.gen());
+                let (x, y) = (mont.element(a), mont.element(b));
+                let (a, b) = ((a % n) as u128, (b % n) as u128);
+                let n128 = n as u128;
+                assert_eq!(x.to_inner() as u128, a);
+                assert_eq!((x + y).to_inner() as u128, (a + b) % n128);
+                assert_eq!((x - y).to_inner() as u128, (a + n128 - b) % n128);
+                assert_eq!((x * y).to_inner() as u128, a * b % n128);
+                assert_eq!((-x).to_inner() as u128, (n128 - a) % n128);
+            }
+        }
+        let mont = Montgomery64::new((1 << 61) - 1);
+        let x = mont.element(123_456_789);
+        assert_eq!(x.pow((1 << 61) - 3), x.inv());
+        assert_eq!(x * x.inv(), mont.one());
+        assert_eq!((x / mont.element(123_456_789)).to_inner(), 1);
+        assert_eq!(mont.element(3).pow(0), mont.one());
+    }
 }
@@ -0,0 +1,462 @@
+use crate::utils::algebraic_traits::{Associative, Pow, SemiRing, Zero};
+use std::ops::{Add, AddAssign, BitXor, Mul, Neg, Rem};
+
+// ------------ monoids start ------------
+
+/// Min, Max の単位元に使う最大値と最小値
+pub trait Bounded: Sized {
+    fn min_value() -> Self;
+    fn max_value() -> Self;
+}
+
+macro_rules! impl_bounded {
+    ($($t:ident,)*) => {
+        $(
+            impl Bounded for $t {
+                fn min_value() -> Self { std::$t::MIN }
+                fn max_value() -> Self { std::$t::MAX }
+            }
+        )*
+    };
+}
+
+impl_bounded! {
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize,
+}
+
+impl Bounded for f64 {
+    fn min_value() -> Self {
+        std::f64::NEG_INFINITY
+    }
+    fn max_value() -> Self {
+        std::f64::INFINITY
+    }
+}
+
+/// 何もしない作用。LazySegmentTree や Treap を作用なしで使うときの E
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Nop;
+
+impl Add for Nop {
+    type Output = Self;
+    fn add(self, _: Self) -> Self {
+        Nop
+    }
+}
+
+impl Associative for Nop {}
+
+impl Zero for Nop {
+    fn zero() -> Self {
+        Nop
+    }
+}
+
+impl Pow for Nop {
+    fn pow(&self, _: u32) -> Self {
+        Nop
+    }
+}
+
+/// 一行で Monoid (と Mul<Nop>) を実装した newtype を定義する
+/// ```ignore
+/// define_monoid!(pub MinIndex((i64, usize)), (std::i64::MAX, 0), |a, b| a.min(b));
+/// ```
+#[macro_export]
+macro_rules! define_monoid {
+    ($vis:vis $name:ident($t:ty), $zero:expr, |$a:ident, $b:ident| $op:expr) => {
+        #[derive(Clone, Debug, PartialEq)]
+        $vis struct $name(pub $t);
+
+        impl std::ops::Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                let ($a, $b) = (self.0, rhs.0);
+                $name($op)
+            }
+        }
+
+        impl $crate::utils::algebraic_traits::Associative for $name {}
+
+        impl $crate::utils::algebraic_traits::Zero for $name {
+            fn zero() -> Self {
+                $name($zero)
+            }
+        }
+
+        impl std::ops::Mul<$crate::utils::monoids::Nop> for $name {
+            type Output = Self;
+            fn mul(self, _: $crate::utils::monoids::Nop) -> Self {
+                self
+            }
+        }
+    };
+}
+
+macro_rules! impl_nop_action {
+    ($($name:ident,)*) => {
+        $(
+            impl<T> Mul<Nop> for $name<T> {
+                type Output = Self;
+                fn mul(self, _: Nop) -> Self {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_nop_action! {
+    Min, Max, Sum, Xor, Gcd, Affine, Last, MatrixProduct,
+}
+
+macro_rules! impl_commutative {
+    ($($name:ident,)*) => {
+        $(
+            impl<T> AddAssign for $name<T> where Self: Add<Output = Self> + Clone {
+                fn add_assign(&mut self, rhs: Self) {
+                    *self = self.clone() + rhs;
+                }
+            }
+        )*
+    };
+}
+
+impl_commutative! {
+    Min, Max, Sum, Xor, Gcd,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Min<T>(pub T);
+
+impl<T: PartialOrd> Add for Min<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        if rhs.0 < self.0 {
+            rhs
+        } else {
+            self
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone> Associative for Min<T> {}
+
+impl<T: PartialOrd + Clone + Bounded> Zero for Min<T> {
+    fn zero() -> Self {
+        Min(T::max_value())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Max<T>(pub T);
+
+impl<T: PartialOrd> Add for Max<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        if rhs.0 > self.0 {
+            rhs
+        } else {
+            self
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone> Associative for Max<T> {}
+
+impl<T: PartialOrd + Clone + Bounded> Zero for Max<T> {
+    fn zero() -> Self {
+        Max(T::min_value())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sum<T>(pub T);
+
+impl<T: Add<Output = T>> Add for Sum<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Sum(self.0 + rhs.0)
+    }
+}
+
+impl<T: Add<Output = T> + Clone + PartialEq> Associative for Sum<T> {}
+
+impl<T: Add<Output = T> + Zero> Zero for Sum<T> {
+    fn zero() -> Self {
+        Sum(T::zero())
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Sum<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Sum(-self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Xor<T>(pub T);
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: BitXor<Output = T>> Add for Xor<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Xor(self.0 ^ rhs.0)
+    }
+}
+
+impl<T: BitXor<Output = T> + Clone + PartialEq> Associative for Xor<T> {}
+
+impl<T: BitXor<Output = T> + Zero> Zero for Xor<T> {
+    fn zero() -> Self {
+        Xor(T::zero())
+    }
+}
+
+/// 各元が自身の逆元
+impl<T> Neg for Xor<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+/// 非負の値に対する最大公約数。単位元は 0
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Gcd<T>(pub T);
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: Copy + Rem<Output = T> + Zero> Add for Gcd<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let (mut a, mut b) = (self.0, rhs.0);
+        while !b.is_zero() {
+            let r = a % b;
+            a = b;
+            b = r;
+        }
+        Gcd(a)
+    }
+}
+
+impl<T: Copy + Rem<Output = T> + Zero> Associative for Gcd<T> {}
+
+impl<T: Copy + Rem<Output = T> + Zero> Zero for Gcd<T> {
+    fn zero() -> Self {
+        Gcd(T::zero())
+    }
+}
+
+/// x => a x + b。f + g は f を適用してから g を適用する関数
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Affine<T>(pub T, pub T);
+
+impl<T: SemiRing> Affine<T> {
+    pub fn apply(&self, x: T) -> T {
+        self.0.clone() * x + self.1.clone()
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: SemiRing> Add for Affine<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Affine(rhs.0.clone() * self.0, rhs.0 * self.1 + rhs.1)
+    }
+}
+
+impl<T: SemiRing> Associative for Affine<T> {}
+
+impl<T: SemiRing> Zero for Affine<T> {
+    fn zero() -> Self {
+        Affine(T::one(), T::zero())
+    }
+}
+
+/// 最後に代入された値。None は何も代入しないことを表す
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Last<T>(pub Option<T>);
+
+impl<T> Add for Last<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        if rhs.0.is_some() {
+            rhs
+        } else {
+            self
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Associative for Last<T> {}
+
+impl<T: Clone + PartialEq> Zero for Last<T> {
+    fn zero() -> Self {
+        Last(None)
+    }
+    fn is_zero(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+impl<T: Clone> Pow for Last<T> {
+    fn pow(&self, _: u32) -> Self {
+        self.clone()
+    }
+}
+
+/// 正方行列の積。None は単位行列を表すので、大きさを決めずに単位元を作れる
+/// f + g は左から順に掛けた積 f * g
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MatrixProduct<T>(pub Option<Vec<Vec<T>>>);
+
+impl<T> MatrixProduct<T> {
+    pub fn new(matrix: Vec<Vec<T>>) -> Self {
+        assert!(
+            matrix.iter().all(|row| row.len() == matrix.len()),
+            "matrix must be square"
+        );
+        MatrixProduct(Some(matrix))
+    }
+}
+
+impl<T: SemiRing> MatrixProduct<T> {
+    /// 大きさ n の行列として取り出す
+    pub fn to_matrix(&self, n: usize) -> Vec<Vec<T>> {
+        match &self.0 {
+            Some(a) => a.clone(),
+            None => (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| if i == j { T::one() } else { T::zero() })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: SemiRing> Add for MatrixProduct<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let (a, b) = match (self.0, rhs.0) {
+            (Some(a), Some(b)) => (a, b),
+            (a, None) => return MatrixProduct(a),
+            (None, b) => return MatrixProduct(b),
+        };
+        let n = a.len();
+        assert_eq!(n, b.len(), "matrix sizes must be equal");
+        let mut c = vec![vec![T::zero(); n]; n];
+        for (ci, ai) in c.iter_mut().zip(&a) {
+            for (aik, bk) in ai.iter().zip(&b) {
+                for (cij, bkj) in ci.iter_mut().zip(bk) {
+                    *cij += aik.clone() * bkj.clone();
+                }
+            }
+        }
+        MatrixProduct(Some(c))
+    }
+}
+
+impl<T: SemiRing> Associative for MatrixProduct<T> {}
+
+impl<T: SemiRing> Zero for MatrixProduct<T> {
+    fn zero() -> Self {
+        MatrixProduct(None)
+    }
+    fn is_zero(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+// ------------ monoids end ------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::swag::SwagQueue;
+    use crate::data_structures::disjoint_sparse_table::DisjointSparseTable;
+    use crate::data_structures::fenwick_tree::FenwickTree;
+    use crate::data_structures::implicit_treap::Treap;
+    use crate::data_structures::segment_trees::segment_tree::SegmentTree;
+
+    #[test]
+    fn test_segment_tree() {
+        let a = [3, 1, 4, 1, 5, 9, 2, 6];
+        let seg = SegmentTree::from(a.iter().map(|&x| Min(x)).collect::<Vec<_>>());
+        assert_eq!(seg.fold(2..6), Min(1));
+        assert_eq!(seg.fold(4..4), Min(std::i32::MAX));
+        let seg = SegmentTree::from(a.iter().map(|&x| Max(x as f64)).collect::<Vec<_>>());
+        assert_eq!(seg.fold(..5), Max(5.));
+
+        // point set range composite
+        let mut seg = SegmentTree::from(vec![Affine(2, 1), Affine(3, 0), Affine(1, 5)]);
+        assert_eq!(seg.fold(..).apply(1), 14);
+        seg.set(0, Affine(1, 0));
+        assert_eq!(seg.fold(..).apply(1), 8);
+        assert_eq!(seg.fold(1..1), Affine(1, 0));
+    }
+
+    #[test]
+    fn test_fenwick_tree() {
+        let mut fw = FenwickTree::from(vec![Sum(1i64), Sum(2), Sum(3), Sum(4)]);
+        fw.add(1, Sum(-5));
+        assert_eq!(fw.sum(1..3), Sum(0));
+        assert_eq!(fw.prefix_sum(4), Sum(5));
+        let fw = FenwickTree::from(vec![Xor(5u32), Xor(3), Xor(6)]);
+        assert_eq!(fw.sum(1..), Xor(5));
+    }
+
+    #[test]
+    fn test_swag_and_sparse_table() {
+        let mut swag = SwagQueue::new();
+        let m = |a, b, c, d| MatrixProduct::new(vec![vec![a, b], vec![c, d]]);
+        swag.push(m(1, 1, 0, 1));
+        swag.push(m(1, 0, 1, 1));
+        assert_eq!(swag.fold_all(), Some(m(2, 1, 1, 1)));
+        swag.pop();
+        swag.push(m(2, 0, 0, 2));
+        assert_eq!(swag.fold_all(), Some(m(2, 0, 2, 2)));
+        let id = MatrixProduct::<i64>::zero();
+        assert_eq!(id.to_matrix(2), vec![vec![1, 0], vec![0, 1]]);
+        assert_eq!(id + m(1, 2, 3, 4), m(1, 2, 3, 4));
+
+        let dst = DisjointSparseTable::from(&vec![Gcd(12u64), Gcd(18), Gcd(8), Gcd(7)]);
+        assert_eq!(dst.fold(0..2), Gcd(6));
+        assert_eq!(dst.fold(0..3), Gcd(2));
+        assert_eq!(dst.fold(2..4), Gcd(1));
+        assert_eq!(Gcd(0) + Gcd(9), Gcd(9));
+    }
+
+    #[test]
+    fn test_treap() {
+        let mut treap = Treap::<Min<i64>, Nop>::from(vec![Min(5), Min(3), Min(8), Min(1)]);
+        assert_eq!(treap.fold(0..3), Min(3));
+        treap.reverse(0..4);
+        treap.remove(0);
+        assert_eq!(treap.fold(0..1), Min(8));
+        let mut treap = Treap::<Last<char>, Nop>::from(vec![Last(Some('a')), Last(None)]);
+        assert_eq!(treap.fold(0..2), Last(Some('a')));
+        treap.insert(1, Last(Some('b')));
+        assert_eq!(treap.fold(0..3), Last(Some('b')));
+    }
+
+    define_monoid!(MinIndex((i64, usize)), (std::i64::MAX, 0), |a, b| a.min(b));
+    define_monoid!(Concat(String), String::new(), |a, b| a + &b);
+
+    #[test]
+    fn test_define_monoid() {
+        let a = [3, 1, 4, 1, 5];
+        let seg = SegmentTree::from((0..5).map(|i| MinIndex((a[i], i))).collect::<Vec<_>>());
+        assert_eq!(seg.fold(..), MinIndex((1, 1)));
+        assert_eq!(seg.fold(2..), MinIndex((1, 3)));
+        let mut treap = Treap::<Concat, Nop>::new();
+        treap.insert(0, Concat("b".to_string()));
+        treap.insert(0, Concat("a".to_string()));
+        treap.insert(2, Concat("c".to_string()));
+        assert_eq!(treap.fold(0..3), Concat("abc".to_string()));
+    }
+}
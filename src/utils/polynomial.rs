@@ -179,6 +179,28 @@ impl NTTFriendly for M998244353 {
     const ZETA: u32 = 15311432;
 }
 
+// 畳み込みの値が大きいときに中国剰余定理で組み合わせる素数
+pub enum M167772161 {}
+pub enum M469762049 {}
+
+impl Modulus for M167772161 {
+    const MODULUS: u32 = 167_772_161;
+}
+
+impl Modulus for M469762049 {
+    const MODULUS: u32 = 469_762_049;
+}
+
+impl NTTFriendly for M167772161 {
+    const ORDER: usize = 33554432;
+    const ZETA: u32 = 243;
+}
+
+impl NTTFriendly for M469762049 {
+    const ORDER: usize = 67108864;
+    const ZETA: u32 = 2187;
+}
+
 pub trait ArrayNTT {
     type Item;
     fn ntt(&mut self);
@@ -1,5 +1,6 @@
 use crate::utils::bitset::BitSet;
 use crate::utils::math::*;
+use crate::utils::modint::Montgomery64;
 
 pub fn atkin_sieve(n: usize) -> Vec<i64> {
     let mut sieve = BitSet::new(n + 1);
@@ -62,11 +63,25 @@ pub fn atkin_sieve(n: usize) -> Vec<i64> {
     sieve.collect().into_iter().map(|x| x as i64).collect()
 }
 
+/// 素因数分解 (素因数の昇順)
+/// x <= 1 なら空
 pub fn factorize(x: i64) -> Vec<(i64, usize)> {
+    if x <= 1 {
+        return Vec::new();
+    }
+    factorize_u64(x as u64)
+        .into_iter()
+        .map(|(p, e)| (p as i64, e))
+        .collect()
+}
+
+/// 素因数分解 (素因数の昇順)
+/// 小さい素因数は試し割りで、残りは Miller-Rabin と pollard_rho で分解する
+pub fn factorize_u64(x: u64) -> Vec<(u64, usize)> {
     let mut y = x;
     let mut res = Vec::new();
-    for i in 2..x + 1 {
-        if i * i > x {
+    for i in 2..x.min(1 << 7) + 1 {
+        if i * i > y {
             break;
         }
         if y % i == 0 {
@@ -79,7 +94,24 @@ pub fn factorize(x: i64) -> Vec<(i64, usize)> {
         }
     }
     if y > 1 {
-        res.push((y, 1));
+        let mut primes = Vec::new();
+        let mut stack = vec![y];
+        while let Some(v) = stack.pop() {
+            if is_prime(v) {
+                primes.push(v);
+            } else {
+                let d = find_factor(v, 1);
+                stack.push(d);
+                stack.push(v / d);
+            }
+        }
+        primes.sort();
+        for p in primes {
+            match res.last_mut() {
+                Some((q, cnt)) if *q == p => *cnt += 1,
+                _ => res.push((p, 1)),
+            }
+        }
     }
     res
 }
@@ -100,62 +132,156 @@ pub fn divisor(x: i64) -> Vec<i64> {
     res
 }
 
-pub fn totient(x: i64) -> i64 {
-    let mut res = x;
-    for &(i, _) in factorize(x).iter() {
-        res = res * (i - 1) / i;
+/// 約数の列挙 (昇順)
+/// 素因数分解を経由するので x が大きくても速い
+pub fn divisors_u64(x: u64) -> Vec<u64> {
+    if x == 0 {
+        return Vec::new();
+    }
+    let mut res = vec![1];
+    for (p, e) in factorize_u64(x) {
+        let len = res.len();
+        let mut q = 1;
+        for _ in 0..e {
+            q *= p;
+            for i in 0..len {
+                res.push(res[i] * q);
+            }
+        }
     }
+    res.sort();
     res
 }
 
-#[allow(clippy::many_single_char_names)]
+pub fn totient(x: i64) -> i64 {
+    if x <= 1 {
+        return x.max(0);
+    }
+    totient_u64(x as u64) as i64
+}
+
+/// オイラーの φ 関数
+pub fn totient_u64(x: u64) -> u64 {
+    factorize_u64(x)
+        .into_iter()
+        .fold(x, |res, (p, _)| res / p * (p - 1))
+}
+
+/// カーマイケル関数: a^λ(x) = 1 (mod x) が x と互いに素な全ての a で成り立つ最小の λ(x)
+pub fn carmichael_lambda(x: u64) -> u64 {
+    assert!(x > 0, "x must be positive");
+    factorize_u64(x)
+        .into_iter()
+        .map(|(p, e)| {
+            if p == 2 && e >= 3 {
+                1 << (e - 2)
+            } else {
+                p.pow(e as u32 - 1) * (p - 1)
+            }
+        })
+        .fold(1, |l, m| l / gcd(l, m) * m)
+}
+
+/// 決定的 Miller-Rabin 素数判定
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+    if n < 37 * 37 {
+        return true;
+    }
+    let mont = Montgomery64::new(n);
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let one = mont.one();
+    let minus_one = -one;
+    // 2^64 未満ではこの 7 個の底で十分
+    for &a in &[2, 325, 9375, 28178, 450_775, 9_780_504, 1_795_265_022] {
+        let a = mont.element(a);
+        if a.is_zero() {
+            continue;
+        }
+        let mut x = a.pow(d);
+        if x == one || x == minus_one {
+            continue;
+        }
+        let mut composite = true;
+        for _ in 1..s {
+            x *= x;
+            if x == minus_one {
+                composite = false;
+                break;
+            }
+        }
+        if composite {
+            return false;
+        }
+    }
+    true
+}
+
+/// v の約数を一つ返す (Brent の改良版)
+/// v が合成数なら非自明な約数を、素数 (と 2 未満) なら自明な約数を返す
+/// seed は乱択の初期値で、失敗したら次の値を試す
 pub fn pollard_rho(v: i64, seed: i64) -> i64 {
     if v == 0 {
         return 1;
     }
-    let seed = seed.wrapping_mul(v);
-    let c = seed & 0xff;
-    let u = c & 0x7f;
-    let mut r: i64 = 1;
-    let mut q: i64 = 1;
-    let mut y: i64 = u & 0x0f;
-    let mut fac: i64 = 1;
-    let mut y_old: i64 = 0;
-    let mut x: i64 = 0;
-    let func = |x: i64| (x.wrapping_mul(x) + c) % v;
-    while fac == 1 {
-        x = y;
-        for _ in 0..r {
-            y = func(y);
-        }
-        let mut k = 0;
-        while k < r && fac == 1 {
-            y_old = y;
-            for _ in 0..std::cmp::min(u, r - k) {
-                y = func(y);
-
-                if x > y {
-                    q = q.wrapping_mul(x - y) % v;
-                } else {
-                    q = q.wrapping_mul(y - x) % v;
+    if v < 2 || is_prime(v as u64) {
+        return v;
+    }
+    find_factor(v as u64, seed as u64) as i64
+}
+
+/// pollard_rho の本体。積は Montgomery 乗算で取るので 2^64 未満ならあふれない
+#[allow(clippy::many_single_char_names)]
+fn find_factor(n: u64, seed: u64) -> u64 {
+    if n % 2 == 0 {
+        return 2;
+    }
+    const M: u64 = 128;
+    let mont = Montgomery64::new(n);
+    for c in seed..std::u64::MAX {
+        let c = mont.element(c);
+        let f = |y| y * y + c;
+        let (mut x, mut y, mut ys) = (mont.one(), mont.element(2), mont.zero());
+        let mut q = mont.one();
+        let mut g = 1;
+        let mut r = 1;
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..M.min(r - k) {
+                    y = f(y);
+                    q *= x - y;
                 }
+                g = gcd(q.to_inner(), n);
+                k += M;
             }
-            fac = gcd(q, v);
-            k += u;
+            r <<= 1;
         }
-        r <<= 1;
-    }
-    while fac == v || fac <= 1 {
-        y_old = func(y_old);
-
-        if x > y_old {
-            q = q.wrapping_mul(x - y_old) % v;
-        } else {
-            q = q.wrapping_mul(y_old - x) % v;
+        if g == n {
+            g = 1;
+            while g == 1 {
+                ys = f(ys);
+                g = gcd((x - ys).to_inner(), n);
+            }
+        }
+        if g != n {
+            return g;
         }
-        fac = gcd(q, v);
     }
-    fac
+    unreachable!()
 }
 
 // * verified: https://judge.yosupo.jp/submission/30338
@@ -345,4 +471,95 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_is_prime() {
+        let primes = prime_brute(1000);
+        for i in 0..=1000 {
+            assert_eq!(is_prime(i), primes.contains(&(i as i64)));
+        }
+        // 強擬素数やカーマイケル数
+        for &n in &[561, 3_215_031_751, 3_825_123_056_546_413_051] {
+            assert!(!is_prime(n));
+        }
+        for &n in &[998_244_353, (1 << 61) - 1, 9_223_372_036_854_775_783] {
+            assert!(is_prime(n));
+        }
+        assert!(!is_prime(1_000_000_007 * 998_244_353));
+    }
+
+    #[test]
+    fn test_factorize() {
+        for x in 1..2000 {
+            let f = factorize(x);
+            assert_eq!(f.iter().map(|&(p, e)| p.pow(e as u32)).product::<i64>(), x);
+            assert!(f.iter().all(|&(p, _)| is_prime(p as u64)));
+            assert!(f.windows(2).all(|w| w[0].0 < w[1].0));
+        }
+        assert_eq!(
+            factorize(1_000_000_007 * 998_244_353),
+            vec![(998_244_353, 1), (1_000_000_007, 1)]
+        );
+        assert_eq!(
+            factorize(4_611_686_014_132_420_609),
+            vec![(2_147_483_647, 2)]
+        );
+        for &v in &[91, 1_000_000_007 * 998_244_353, 4_611_686_014_132_420_609] {
+            let d = pollard_rho(v, 1);
+            assert!(1 < d && d < v && v % d == 0);
+        }
+        assert_eq!(pollard_rho(998_244_353, 1), 998_244_353);
+        assert_eq!(pollard_rho(0, 1), 1);
+    }
+
+    #[test]
+    fn test_factorize_u64() {
+        assert_eq!(factorize_u64(0), vec![]);
+        assert_eq!(factorize_u64(1), vec![]);
+        // 2^63 を超える値
+        assert_eq!(
+            factorize_u64(std::u64::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (6_700_417, 1)
+            ]
+        );
+        assert_eq!(factorize_u64(1 << 63), vec![(2, 63)]);
+        let p = 4_294_967_291; // 2^32 未満の最大の素数
+        assert_eq!(factorize_u64(p * p), vec![(p, 2)]);
+        let q = 18_446_744_073_709_551_557; // 2^64 未満の最大の素数
+        assert!(is_prime(q));
+        assert_eq!(factorize_u64(q), vec![(q, 1)]);
+        assert_eq!(
+            factorize_u64(3_825_123_056_546_413_051),
+            vec![(149_491, 1), (747_451, 1), (34_233_211, 1)]
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_functions() {
+        for x in 1..500u64 {
+            let brute = (1..=x).filter(|d| x % d == 0).collect::<Vec<_>>();
+            assert_eq!(divisors_u64(x), brute);
+            let coprime = (1..=x).filter(|&a| gcd(a, x) == 1).collect::<Vec<_>>();
+            assert_eq!(totient_u64(x), coprime.len() as u64);
+            assert_eq!(totient(x as i64), coprime.len() as i64);
+            let lambda = (1..=x)
+                .find(|&l| {
+                    coprime
+                        .iter()
+                        .all(|&a| modpow(a as i64, l as i64, x as i64) == 1 % x as i64)
+                })
+                .unwrap();
+            assert_eq!(carmichael_lambda(x), lambda);
+        }
+        assert_eq!(divisors_u64(std::u64::MAX).len(), 128);
+        assert_eq!(totient_u64(std::u64::MAX), 9_208_981_628_670_443_520);
+        assert_eq!(carmichael_lambda(std::u64::MAX), 17_153_064_960);
+    }
 }
@@ -1,9 +1,31 @@
+use rand::{Error, RngCore, SeedableRng};
+
 #[derive(Clone, Debug)]
 pub struct XorShift(u64);
 
 impl XorShift {
-    pub fn new(seed: u64) -> Self { Self(seed) }
-    pub fn gen(&mut self) -> u64 {
+    /// seed が 0 なら既定の値を使う
+    pub fn new(seed: u64) -> Self {
+        Self::from_seed(seed.to_le_bytes())
+    }
+    /// 以前の XorShift::gen。rand::Rng::gen と名前が衝突するので改名した
+    pub fn gen_u64(&mut self) -> u64 {
+        self.next_u64()
+    }
+}
+
+impl Default for XorShift {
+    fn default() -> Self {
+        Self(88172645463325252)
+    }
+}
+
+// rand::Rng として使えるようにする
+impl RngCore for XorShift {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+    fn next_u64(&mut self) -> u64 {
         let x = self.0;
         let x = (x << 13) ^ x;
         let x = (x >> 7) ^ x;
@@ -11,11 +33,26 @@ impl XorShift {
         self.0 = x;
         x
     }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
 }
 
-impl Default for XorShift {
-    fn default() -> Self {
-        Self(88172645463325252)
+impl SeedableRng for XorShift {
+    type Seed = [u8; 8];
+    /// seed が 0 だと 0 しか出力しないので既定の値を使う
+    fn from_seed(seed: [u8; 8]) -> Self {
+        match u64::from_le_bytes(seed) {
+            0 => Self::default(),
+            seed => Self(seed),
+        }
     }
 }
 
@@ -26,10 +63,30 @@ mod tests {
     #[test]
     fn test_xor_shift() {
         let mut rand = XorShift::default();
-        let m = 100000; 
-        let mut result: Vec<u64> = (0..m).map(|_| rand.gen()).collect();
+        let m = 100000;
+        let mut result: Vec<u64> = (0..m).map(|_| rand.next_u64()).collect();
         result.sort();
         result.dedup();
         assert_eq!(result.len(), m)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_rng() {
+        use rand::Rng;
+        let mut a = XorShift::seed_from_u64(42);
+        let mut b = XorShift::seed_from_u64(42);
+        for _ in 0..100 {
+            let x = a.gen_range(0..10);
+            assert!(x < 10);
+            assert_eq!(x, b.gen_range(0..10));
+        }
+        let mut c = XorShift::from_seed([0; 8]);
+        assert_ne!(c.next_u64(), 0);
+        // Rng::gen が使える
+        let x: f64 = a.gen();
+        assert!((0. ..1.).contains(&x));
+        assert_eq!(x.to_bits(), b.gen::<f64>().to_bits());
+        assert_eq!(a.gen_u64(), b.next_u64());
+        assert_ne!(XorShift::new(0).gen_u64(), 0);
+    }
+}